@@ -0,0 +1,35 @@
+-- Add migration script here
+
+CREATE TABLE "event" (
+    "event_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "venue_id" INTEGER NOT NULL,
+    "date" TEXT NOT NULL,
+
+    UNIQUE ("venue_id", "date"),
+    FOREIGN KEY ("venue_id") REFERENCES "venue" ("venue_id")
+);
+
+INSERT INTO "event" ("venue_id", "date")
+SELECT DISTINCT "venue_id", "date" FROM "gig" ORDER BY "date";
+
+-- A gig is now one artist's slot in an event's lineup, `position` is the running order
+CREATE TABLE "new_gig" (
+    "event_id" INTEGER NOT NULL,
+    "artist_id" INTEGER NOT NULL,
+    "act" INTEGER NOT NULL,
+    "position" INTEGER NOT NULL,
+
+    PRIMARY KEY ("event_id", "artist_id"),
+    FOREIGN KEY ("event_id") REFERENCES "event" ("event_id"),
+    FOREIGN KEY ("artist_id") REFERENCES "artist" ("artist_id")
+);
+
+INSERT INTO "new_gig"
+SELECT "e"."event_id", "g"."artist_id", "g"."act",
+       ROW_NUMBER() OVER (PARTITION BY "e"."event_id" ORDER BY "g"."act", "g"."artist_id") - 1
+FROM "gig" "g"
+INNER JOIN "event" "e" ON "e"."venue_id" = "g"."venue_id" AND "e"."date" = "g"."date";
+
+DROP TABLE "gig";
+
+ALTER TABLE "new_gig" RENAME TO "gig";
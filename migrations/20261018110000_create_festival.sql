@@ -0,0 +1,27 @@
+-- Add migration script here
+
+CREATE TABLE "festival" (
+    "festival_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL,
+    -- The site the festival is held at
+    "venue_id" INTEGER NOT NULL,
+    "start_date" TEXT NOT NULL,
+    "end_date" TEXT NOT NULL,
+
+    UNIQUE ("name", "start_date"),
+    FOREIGN KEY ("venue_id") REFERENCES "venue" ("venue_id")
+);
+
+CREATE TABLE "stage" (
+    "stage_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "festival_id" INTEGER NOT NULL,
+    "name" TEXT NOT NULL,
+
+    UNIQUE ("festival_id", "name"),
+    FOREIGN KEY ("festival_id") REFERENCES "festival" ("festival_id")
+);
+
+-- Every night of a festival is an event at the festival's site
+ALTER TABLE "event" ADD COLUMN "festival_id" INTEGER REFERENCES "festival" ("festival_id");
+
+ALTER TABLE "gig" ADD COLUMN "stage_id" INTEGER REFERENCES "stage" ("stage_id");
@@ -0,0 +1,8 @@
+-- Add migration script here
+
+-- A ticket covers everyone on the lineup, so it belongs to the event rather than each gig.
+-- `price` and `fees` are in minor units (pence, cents)
+ALTER TABLE "event" ADD COLUMN "price" INTEGER;
+ALTER TABLE "event" ADD COLUMN "fees" INTEGER;
+ALTER TABLE "event" ADD COLUMN "currency" TEXT;
+ALTER TABLE "event" ADD COLUMN "ticket_type" TEXT;
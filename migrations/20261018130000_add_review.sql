@@ -0,0 +1,6 @@
+-- Add migration script here
+
+-- Each artist's set is rated on its own, so these belong to the gig rather than the event.
+-- `rating` is out of 5
+ALTER TABLE "gig" ADD COLUMN "rating" INTEGER;
+ALTER TABLE "gig" ADD COLUMN "notes" TEXT;
@@ -0,0 +1,17 @@
+-- Add migration script here
+
+-- One song in a gig's setlist, `position` is the order it was played in.
+-- `cover_of` is the original artist when the song is a cover
+CREATE TABLE "song" (
+    "event_id" INTEGER NOT NULL,
+    "artist_id" INTEGER NOT NULL,
+    "position" INTEGER NOT NULL,
+    "title" TEXT NOT NULL,
+    "encore" BOOLEAN NOT NULL DEFAULT FALSE,
+    "cover_of" TEXT,
+    "notes" TEXT,
+
+    PRIMARY KEY ("event_id", "artist_id", "position"),
+    FOREIGN KEY ("event_id", "artist_id") REFERENCES "gig" ("event_id", "artist_id")
+        ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- Add migration script here
+
+CREATE TABLE "person" (
+    "person_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL UNIQUE
+);
+
+-- Who came along to an event. Everyone sees the whole lineup, so it belongs to the event
+-- rather than each gig
+CREATE TABLE "attendance" (
+    "event_id" INTEGER NOT NULL,
+    "person_id" INTEGER NOT NULL,
+
+    PRIMARY KEY ("event_id", "person_id"),
+    FOREIGN KEY ("event_id") REFERENCES "event" ("event_id") ON DELETE CASCADE,
+    FOREIGN KEY ("person_id") REFERENCES "person" ("person_id")
+);
@@ -0,0 +1,20 @@
+-- Add migration script here
+
+-- One of the people sharing the database, each with the gigs they've been to
+CREATE TABLE "user" (
+    "user_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL UNIQUE
+);
+
+-- A gig a user was at. Someone might leave before the headliner, so it belongs to each gig
+-- rather than the event
+CREATE TABLE "user_gig" (
+    "user_id" INTEGER NOT NULL,
+    "event_id" INTEGER NOT NULL,
+    "artist_id" INTEGER NOT NULL,
+
+    PRIMARY KEY ("user_id", "event_id", "artist_id"),
+    FOREIGN KEY ("user_id") REFERENCES "user" ("user_id") ON DELETE CASCADE,
+    FOREIGN KEY ("event_id", "artist_id") REFERENCES "gig" ("event_id", "artist_id")
+        ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+-- Add migration script here
+
+-- A genre or any other label to group artists by, e.g. "indie" or "local"
+CREATE TABLE "tag" (
+    "tag_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE "artist_tag" (
+    "artist_id" INTEGER NOT NULL,
+    "tag_id" INTEGER NOT NULL,
+
+    PRIMARY KEY ("artist_id", "tag_id"),
+    FOREIGN KEY ("artist_id") REFERENCES "artist" ("artist_id") ON DELETE CASCADE,
+    FOREIGN KEY ("tag_id") REFERENCES "tag" ("tag_id") ON DELETE CASCADE
+);
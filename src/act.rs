@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Act {
@@ -55,3 +55,19 @@ impl Display for Act {
         }
     }
 }
+
+impl FromStr for Act {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "main" | "main act" => Ok(Act::Main),
+            "support" | "support act" => Ok(Act::Support),
+            "shared" | "shared headliner" => Ok(Act::Shared),
+            _ => Err(format!(
+                "Unknown act \"{}\", expected one of main, support or shared",
+                s
+            )),
+        }
+    }
+}
@@ -1,16 +1,30 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
-use ratatui::widgets::{ListItem, Row};
 use sqlx::{Pool, Sqlite};
 
-use crate::{dataset::DataSet, error::Error};
+use crate::{
+    database::Names,
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    event,
+    query::Filter,
+    tag,
+};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Artist {
     pub artist_id: i64,
     pub name: String,
     pub city_id: i64,
-    city_name: Option<String>,
+    pub city_name: Option<String>,
+    // Genres and other tags, e.g. "indie" and "post-punk"
+    pub tags: Vec<String>,
+}
+
+impl PartialOrd for Artist {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for Artist {
@@ -26,40 +40,194 @@ impl Artist {
             name,
             city_id,
             city_name: None,
+            tags: Vec::new(),
         }
     }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+
+        self
+    }
 }
 
 impl DataSet for Artist {
+    const FILTERS: &'static [&'static str] = &["city", "tag"];
+
     async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
-        Ok(sqlx::query_as!(Artist, "SELECT 'a'.'artist_id', 'a'.'name', 'a'.'city_id', 'c'.'name' AS 'city_name' FROM 'artist' a INNER JOIN 'city' c ON 'c'.'city_id' = 'a'.'city_id'")
-            .fetch_all(pool)
-            .await?)
+        Ok(sqlx::query_as!(
+            Artist,
+            r#"
+            SELECT 'a'.'artist_id', 'a'.'name', 'a'.'city_id', 'c'.'name' AS 'city_name',
+                   (SELECT json_group_array("name") FROM (
+                       SELECT "tag"."name" FROM "artist_tag"
+                       INNER JOIN "tag" ON "tag"."tag_id" = "artist_tag"."tag_id"
+                       WHERE "artist_tag"."artist_id" = "a"."artist_id"
+                       ORDER BY "tag"."name" COLLATE NOCASE
+                   )) AS "tags!: Names"
+            FROM 'artist' a INNER JOIN 'city' c ON 'c'.'city_id' = 'a'.'city_id'"#
+        )
+        .fetch_all(pool)
+        .await?)
     }
 
     async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
-        sqlx::query("INSERT INTO artist (\"name\", \"city_id\") VALUES ($1, $2)")
-            .bind(val.name)
+        let mut tx = pool.begin().await?;
+
+        let artist_id: i64 = sqlx::query_scalar(
+            "INSERT INTO artist (\"name\", \"city_id\") VALUES ($1, $2) RETURNING \"artist_id\"",
+        )
+        .bind(&val.name)
+        .bind(val.city_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tag::set_artist_tags(&mut tx, artist_id, &val.tags).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("UPDATE artist SET \"name\" = $1, \"city_id\" = $2 WHERE \"artist_id\" = $3")
+            .bind(&val.name)
             .bind(val.city_id)
-            .execute(pool)
+            .bind(original.artist_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tag::set_artist_tags(&mut tx, original.artist_id, &val.tags).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            DeleteStrategy::Cascade => {
+                sqlx::query("DELETE FROM \"gig\" WHERE \"artist_id\" = $1")
+                    .bind(val.artist_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                event::remove_empty(&mut tx).await?;
+            }
+            DeleteStrategy::Reassign(replacement) => {
+                // Nights the replacement was on the lineup for too are merged into their gig
+                let shared: Vec<i64> = sqlx::query_scalar(
+                    r#"
+                    SELECT "event_id" FROM "gig"
+                    WHERE "artist_id" = $2
+                      AND "event_id" IN (SELECT "event_id" FROM "gig" WHERE "artist_id" = $1)"#,
+                )
+                .bind(replacement.artist_id)
+                .bind(val.artist_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+                for event_id in shared {
+                    event::merge_gig(
+                        &mut tx,
+                        (event_id, val.artist_id),
+                        (event_id, replacement.artist_id),
+                    )
+                    .await?;
+                }
+
+                sqlx::query("UPDATE \"gig\" SET \"artist_id\" = $1 WHERE \"artist_id\" = $2")
+                    .bind(replacement.artist_id)
+                    .bind(val.artist_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                // The replacement keeps its own tags and picks up any it was missing
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO "artist_tag" ("artist_id", "tag_id")
+                    SELECT $1, "tag_id" FROM "artist_tag" WHERE "artist_id" = $2"#,
+                )
+                .bind(replacement.artist_id)
+                .bind(val.artist_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM \"artist\" WHERE \"artist_id\" = $1")
+            .bind(val.artist_id)
+            .execute(&mut *tx)
             .await?;
 
+        tag::remove_unused(&mut tx).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 
-    fn contains(&self, val: String) -> bool {
-        let search = val.as_str().to_lowercase();
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let gigs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM \"gig\" WHERE \"artist_id\" = $1")
+            .bind(self.artist_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(vec![Dependent {
+            table: "gigs",
+            count: gigs,
+        }])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.artist_id.to_string(),
+            self.name.clone(),
+            self.city_name.clone().unwrap_or_default(),
+            self.tags.join(", "),
+        ]
+    }
 
-        self.name.to_lowercase().contains(&search)
-            || self
+    fn matches(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::City(city) => self
                 .city_name
                 .as_ref()
-                .is_some_and(|name| name.to_lowercase().contains(&search))
+                .is_some_and(|name| name.to_lowercase().contains(city)),
+            Filter::Tag(tag) => self
+                .tags
+                .iter()
+                .any(|name| name.to_lowercase().contains(tag)),
+            _ => true,
+        }
     }
 
     fn key(&self) -> impl Ord + Clone {
         &self.name
     }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => self.artist_id.cmp(&other.artist_id),
+            2 => self.city_name.cmp(&other.city_name),
+            3 => self.tags.cmp(&other.tags),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
 }
 
 impl Display for Artist {
@@ -67,19 +235,3 @@ impl Display for Artist {
         write!(f, "{}", self.name.clone())
     }
 }
-
-impl From<Artist> for Row<'_> {
-    fn from(value: Artist) -> Self {
-        Row::new(vec![
-            value.artist_id.to_string(),
-            value.name,
-            value.city_name.unwrap(),
-        ])
-    }
-}
-
-impl From<Artist> for ListItem<'_> {
-    fn from(value: Artist) -> Self {
-        ListItem::new(value.name.clone())
-    }
-}
@@ -1,14 +1,23 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
-use ratatui::widgets::{ListItem, Row};
 use sqlx::{Pool, Sqlite};
 
-use crate::{dataset::DataSet, error::Error};
+use crate::{
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    event, festival, tag,
+};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct City {
     pub city_id: i64,
-    name: String,
+    pub name: String,
+}
+
+impl PartialOrd for City {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for City {
@@ -39,15 +48,165 @@ impl DataSet for City {
         Ok(())
     }
 
-    fn contains(&self, val: String) -> bool {
-        let search = val.as_str().to_lowercase();
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        sqlx::query("UPDATE \"city\" SET \"name\" = $1 WHERE \"city_id\" = $2")
+            .bind(val.name)
+            .bind(original.city_id)
+            .execute(pool)
+            .await?;
 
-        self.name.to_lowercase().contains(&search)
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            DeleteStrategy::Cascade => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM "gig"
+                    WHERE "artist_id" IN (SELECT "artist_id" FROM "artist" WHERE "city_id" = $1)
+                       OR "event_id" IN (
+                           SELECT "event_id" FROM "event"
+                           INNER JOIN "venue" ON "venue"."venue_id" = "event"."venue_id"
+                           WHERE "venue"."city_id" = $1
+                       )"#,
+                )
+                .bind(val.city_id)
+                .execute(&mut *tx)
+                .await?;
+
+                event::remove_empty(&mut tx).await?;
+
+                sqlx::query("DELETE FROM \"artist\" WHERE \"city_id\" = $1")
+                    .bind(val.city_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                let venues: Vec<i64> =
+                    sqlx::query_scalar("SELECT \"venue_id\" FROM \"venue\" WHERE \"city_id\" = $1")
+                        .bind(val.city_id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+
+                for venue_id in venues {
+                    festival::remove_at(&mut tx, venue_id).await?;
+                }
+
+                sqlx::query("DELETE FROM \"venue\" WHERE \"city_id\" = $1")
+                    .bind(val.city_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            DeleteStrategy::Reassign(replacement) => {
+                sqlx::query("UPDATE \"artist\" SET \"city_id\" = $1 WHERE \"city_id\" = $2")
+                    .bind(replacement.city_id)
+                    .bind(val.city_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("UPDATE \"venue\" SET \"city_id\" = $1 WHERE \"city_id\" = $2")
+                    .bind(replacement.city_id)
+                    .bind(val.city_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM \"city\" WHERE \"city_id\" = $1")
+            .bind(val.city_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Any tags only the city's artists had go with them
+        tag::remove_unused(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let artists: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM \"artist\" WHERE \"city_id\" = $1")
+                .bind(self.city_id)
+                .fetch_one(pool)
+                .await?;
+
+        let venues: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM \"venue\" WHERE \"city_id\" = $1")
+                .bind(self.city_id)
+                .fetch_one(pool)
+                .await?;
+
+        let gigs: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM "gig"
+            WHERE "artist_id" IN (SELECT "artist_id" FROM "artist" WHERE "city_id" = $1)
+               OR "event_id" IN (
+                   SELECT "event_id" FROM "event"
+                   INNER JOIN "venue" ON "venue"."venue_id" = "event"."venue_id"
+                   WHERE "venue"."city_id" = $1
+               )"#,
+        )
+        .bind(self.city_id)
+        .fetch_one(pool)
+        .await?;
+
+        let festivals: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM "festival"
+            INNER JOIN "venue" ON "venue"."venue_id" = "festival"."venue_id"
+            WHERE "venue"."city_id" = $1"#,
+        )
+        .bind(self.city_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(vec![
+            Dependent {
+                table: "artists",
+                count: artists,
+            },
+            Dependent {
+                table: "venues",
+                count: venues,
+            },
+            Dependent {
+                table: "gigs",
+                count: gigs,
+            },
+            Dependent {
+                table: "festivals",
+                count: festivals,
+            },
+        ])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.city_id.to_string(), self.name.clone()]
     }
 
     fn key(&self) -> impl Ord + Clone {
         &self.name
     }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => self.city_id.cmp(&other.city_id),
+            _ => self.key().cmp(&other.key()),
+        }
+    }
 }
 
 impl Display for City {
@@ -55,15 +214,3 @@ impl Display for City {
         write!(f, "{}", self.name.clone())
     }
 }
-
-impl From<City> for Row<'_> {
-    fn from(value: City) -> Self {
-        Row::new(vec![value.city_id.to_string(), value.name])
-    }
-}
-
-impl From<City> for ListItem<'_> {
-    fn from(value: City) -> Self {
-        ListItem::new(value.name.clone())
-    }
-}
@@ -0,0 +1,420 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    act::Act,
+    artist::Artist,
+    city::City,
+    dataset::DataSet,
+    date::Date,
+    error::Error,
+    export,
+    festival::Festival,
+    gig::Gig,
+    import,
+    person::Person,
+    setlist::PlayedSong,
+    tag,
+    user::{self, User},
+    venue::Venue,
+};
+
+/// Track the gigs you've been to. Launches the TUI when no command is given.
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Only show the gigs this user has been to, and record new ones as theirs
+    #[arg(long, global = true)]
+    pub user: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add a new record
+    Add {
+        #[command(subcommand)]
+        record: AddCommand,
+    },
+    /// List every record in a table
+    List {
+        #[command(subcommand)]
+        table: ListCommand,
+    },
+    /// Print a summary of every gig
+    Stats,
+    /// Import gigs from a CSV file with `artist,venue,city,date,act` columns, or a JSON export
+    Import {
+        file: PathBuf,
+        /// Worked out from the file extension if not given
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+    /// Export every table to a JSON file, or a directory of CSV files
+    Export {
+        path: PathBuf,
+        /// Worked out from the file extension if not given
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Csv,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AddCommand {
+    City {
+        #[arg(long)]
+        name: String,
+    },
+    Artist {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        city: String,
+        /// A genre or other tag, can be given more than once or as a comma separated list
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    Venue {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        city: String,
+    },
+    Gig {
+        #[arg(long)]
+        artist: String,
+        #[arg(long)]
+        venue: String,
+        /// e.g. 2024/06/01, 2024-06-01, 01/06/2024, "1 Jun 2024" or yesterday
+        #[arg(long)]
+        date: Date,
+        /// One of main, support or shared
+        #[arg(long, default_value = "main")]
+        act: Act,
+        /// e.g. 45 or 45.50
+        #[arg(long)]
+        price: Option<String>,
+        /// Booking fees on top of the price
+        #[arg(long)]
+        fees: Option<String>,
+        /// Needed with a price or fees, e.g. GBP
+        #[arg(long)]
+        currency: Option<String>,
+        /// e.g. Standing, Seated or VIP
+        #[arg(long)]
+        ticket_type: Option<String>,
+        /// Out of 5
+        #[arg(long)]
+        rating: Option<i64>,
+        #[arg(long)]
+        notes: Option<String>,
+        /// Someone who came along, can be given more than once
+        #[arg(long = "with")]
+        people: Vec<String>,
+    },
+    Person {
+        #[arg(long)]
+        name: String,
+    },
+    User {
+        #[arg(long)]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ListCommand {
+    Cities,
+    Artists,
+    Venues,
+    Festivals,
+    People,
+    Users,
+    Gigs {
+        #[arg(long)]
+        year: Option<u32>,
+    },
+}
+
+pub async fn run(command: Command, user: Option<User>, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    match command {
+        Command::Add { record } => add(record, user, pool).await,
+        Command::List { table } => list(table, user, pool).await,
+        Command::Stats => stats(user, pool).await,
+        Command::Import { file, format } => {
+            let report = match format.unwrap_or_else(|| Format::from_path(&file)) {
+                Format::Json => import::import_json(File::open(file)?, user.as_ref(), pool).await?,
+                Format::Csv => import::import_gigs(File::open(file)?, user.as_ref(), pool).await?,
+            };
+            print!("{}", report);
+
+            Ok(())
+        }
+        Command::Export { path, format } => {
+            match format.unwrap_or_else(|| Format::from_path(&path)) {
+                Format::Json => export::export_json(File::create(path)?, pool).await,
+                Format::Csv => export::export_csv(&path, pool).await,
+            }
+        }
+    }
+}
+
+async fn add(record: AddCommand, user: Option<User>, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    match record {
+        AddCommand::City { name } => City::save(City::new(name), pool).await,
+        AddCommand::Artist { name, city, tags } => {
+            let city = find_by_name::<City>(&city, "city", pool).await?;
+
+            Artist::save(
+                Artist::new(name, city.city_id).with_tags(tag::parse(&tags)),
+                pool,
+            )
+            .await
+        }
+        AddCommand::Venue { name, city } => {
+            let city = find_by_name::<City>(&city, "city", pool).await?;
+
+            Venue::save(Venue::new(name, city.city_id), pool).await
+        }
+        AddCommand::Gig {
+            artist,
+            venue,
+            date,
+            act,
+            price,
+            fees,
+            currency,
+            ticket_type,
+            rating,
+            notes,
+            people,
+        } => {
+            let artist = find_by_name::<Artist>(&artist, "artist", pool).await?;
+            let venue = find_by_name::<Venue>(&venue, "venue", pool).await?;
+
+            let mut with = Vec::new();
+            for name in people {
+                with.push(find_by_name::<Person>(&name, "person", pool).await?);
+            }
+
+            let gig = Gig::validate(Some(artist), Some(venue), Some(date), Some(act))?
+                .with_ticket(price, fees, currency, ticket_type)?
+                .with_review(rating, notes)?
+                .with_people(with)
+                .with_users(user.into_iter().collect());
+
+            Gig::save(gig, pool).await
+        }
+        AddCommand::Person { name } => Person::save(Person::new(name), pool).await,
+        AddCommand::User { name } => User::save(User::new(name), pool).await,
+    }
+}
+
+async fn list(table: ListCommand, user: Option<User>, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    match table {
+        ListCommand::Cities => {
+            for city in sorted(City::load_all(pool).await?) {
+                println!("{}\t{}", city.city_id, city.name);
+            }
+        }
+        ListCommand::Artists => {
+            for artist in sorted(Artist::load_all(pool).await?) {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    artist.artist_id,
+                    artist.name,
+                    artist.city_name.unwrap_or_default(),
+                    artist.tags.join(", ")
+                );
+            }
+        }
+        ListCommand::Venues => {
+            for venue in sorted(Venue::load_all(pool).await?) {
+                println!(
+                    "{}\t{}\t{}",
+                    venue.venue_id,
+                    venue.name,
+                    venue.city_name.unwrap_or_default()
+                );
+            }
+        }
+        ListCommand::Festivals => {
+            for festival in sorted(Festival::load_all(pool).await?) {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    festival.dates(),
+                    festival.name,
+                    festival.venue_name.unwrap_or_default(),
+                    festival.stages
+                );
+            }
+        }
+        ListCommand::People => {
+            let people = Person::load_scoped(pool, &user::scope(user.as_ref())).await?;
+            for person in sorted(people) {
+                println!("{}\t{} gigs", person.name, person.gigs);
+            }
+        }
+        ListCommand::Users => {
+            for user in sorted(User::load_all(pool).await?) {
+                println!("{}\t{} gigs", user.name, user.gigs);
+            }
+        }
+        ListCommand::Gigs { year } => {
+            let gigs = sorted(user::gigs_for(user.as_ref(), Gig::load_all(pool).await?))
+                .into_iter()
+                .filter(|gig| year.is_none_or(|year| gig.date.year == year));
+
+            for gig in gigs {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    gig.date,
+                    gig.artist_name.unwrap_or_default(),
+                    gig.venue_name.unwrap_or_default(),
+                    gig.act
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn stats(user: Option<User>, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    let gigs = user::gigs_for(user.as_ref(), Gig::load_all(pool).await?);
+
+    let mut artists: Vec<i64> = gigs.iter().map(|gig| gig.artist_id).collect();
+    artists.sort_unstable();
+    artists.dedup();
+
+    let mut venues: Vec<i64> = gigs.iter().map(|gig| gig.venue_id).collect();
+    venues.sort_unstable();
+    venues.dedup();
+
+    println!("Gigs: {}", gigs.len());
+    println!("Artists seen: {}", artists.len());
+    println!("Venues visited: {}", venues.len());
+
+    for act in [Act::Main, Act::Support, Act::Shared] {
+        println!(
+            "{}: {}",
+            act,
+            gigs.iter().filter(|gig| gig.act == act).count()
+        );
+    }
+
+    let mut per_year: BTreeMap<u32, usize> = BTreeMap::new();
+    for gig in &gigs {
+        *per_year.entry(gig.date.year).or_default() += 1;
+    }
+
+    println!();
+    for (year, count) in per_year {
+        println!("{}\t{}", year, count);
+    }
+
+    let festivals = sorted(Festival::load_all(pool).await?);
+    if !festivals.is_empty() {
+        println!();
+    }
+
+    for festival in festivals {
+        // An artist playing more than one day is only counted once
+        let artists: HashSet<i64> = gigs
+            .iter()
+            .filter(|gig| gig.festival_id == Some(festival.festival_id))
+            .map(|gig| gig.artist_id)
+            .collect();
+
+        println!(
+            "{}\t{}\t{} artists",
+            festival.dates(),
+            festival.name,
+            artists.len()
+        );
+    }
+
+    // The nights spent with each person, however many acts were on
+    let mut people: HashMap<&str, HashSet<i64>> = HashMap::new();
+    for gig in &gigs {
+        for name in &gig.people {
+            people
+                .entry(name.as_str())
+                .or_default()
+                .insert(gig.event_id);
+        }
+    }
+
+    let mut people: Vec<(&str, usize)> = people
+        .into_iter()
+        .map(|(name, nights)| (name, nights.len()))
+        .collect();
+    people.sort_unstable_by(|(l_name, l), (r_name, r)| r.cmp(l).then_with(|| l_name.cmp(r_name)));
+    if !people.is_empty() {
+        println!();
+    }
+
+    for (name, count) in people {
+        println!("With {}\t{} gigs", name, count);
+    }
+
+    let songs = PlayedSong::load_all(pool)
+        .await?
+        .into_iter()
+        .filter(|song| gigs.iter().any(|gig| song.played_at(gig)));
+
+    let mut heard: HashMap<(String, String), usize> = HashMap::new();
+    for song in songs {
+        *heard.entry((song.name(), song.artist_name)).or_default() += 1;
+    }
+
+    let most_heard = heard
+        .into_iter()
+        .max_by(|((l_name, _), l), ((r_name, _), r)| l.cmp(r).then_with(|| r_name.cmp(l_name)));
+
+    if let Some(((name, artist), count)) = most_heard {
+        println!();
+        println!("Most heard song: {} by {} ({} times)", name, artist, count);
+    }
+
+    Ok(())
+}
+
+pub async fn find_by_name<T: DataSet>(
+    name: &str,
+    kind: &str,
+    pool: &Pool<Sqlite>,
+) -> Result<T, Error> {
+    T::load_all(pool)
+        .await?
+        .into_iter()
+        .find(|value| value.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| Error::Str(format!("Unknown {} \"{}\"", kind, name)))
+}
+
+fn sorted<T: DataSet>(mut values: Vec<T>) -> Vec<T> {
+    values.sort_unstable_by(|l, r| l.key().cmp(&r.key()));
+    values
+}
@@ -8,15 +8,43 @@ use ratatui::{
 use sqlx::{Pool, Sqlite};
 
 use crate::{
-    artist::Artist, city::City, datatable::DataTable, error::Error, gig::Gig, venue::Venue,
+    artist::Artist,
+    city::City,
+    datatable::{DataTable, DataTableEvent},
+    error::Error,
+    festival::Festival,
+    gig::Gig,
+    gigdetail::GigDetailPane,
+    person::Person,
+    record::Record,
+    user::{self, User},
+    venue::Venue,
 };
 
+pub enum DataColumnEvent {
+    Open(Record),
+    Edit(Record),
+    Delete(Record),
+}
+
+impl<T> DataTableEvent<T> {
+    fn into_column_event(self, into_record: impl Fn(T) -> Record) -> DataColumnEvent {
+        match self {
+            DataTableEvent::Open(value) => DataColumnEvent::Open(into_record(value)),
+            DataTableEvent::Edit(value) => DataColumnEvent::Edit(into_record(value)),
+            DataTableEvent::Delete(value) => DataColumnEvent::Delete(into_record(value)),
+        }
+    }
+}
+
 #[derive(Hash, PartialEq, Eq, Clone)]
 pub enum TableName {
     Artist,
     Gig,
     Venue,
     City,
+    Festival,
+    Person,
 }
 
 impl TableName {
@@ -25,7 +53,9 @@ impl TableName {
             TableName::Artist => TableName::Venue,
             TableName::Venue => TableName::Gig,
             TableName::Gig => TableName::City,
-            TableName::City => TableName::City,
+            TableName::City => TableName::Festival,
+            TableName::Festival => TableName::Person,
+            TableName::Person => TableName::Person,
         }
     }
 
@@ -35,6 +65,8 @@ impl TableName {
             TableName::Venue => TableName::Artist,
             TableName::Gig => TableName::Venue,
             TableName::City => TableName::Gig,
+            TableName::Festival => TableName::City,
+            TableName::Person => TableName::Festival,
         }
     }
 }
@@ -46,6 +78,10 @@ pub struct DataColumn<'a> {
     venue_table: DataTable<'a, Venue>,
     gig_table: DataTable<'a, Gig>,
     city_table: DataTable<'a, City>,
+    festival_table: DataTable<'a, Festival>,
+    person_table: DataTable<'a, Person>,
+
+    gig_detail: GigDetailPane,
 
     pub focused_app: TableName,
 }
@@ -55,8 +91,8 @@ impl DataColumn<'_> {
         let artist_table = DataTable::new(
             "Artist",
             pool.clone(),
-            [Constraint::Length(20); 3].to_vec(),
-            vec!["Artist Id", "Name", "City Name"],
+            [Constraint::Length(20); 4].to_vec(),
+            vec!["Artist Id", "Name", "City Name", "Tags"],
         )
         .await?;
 
@@ -71,8 +107,8 @@ impl DataColumn<'_> {
         let gig_table = DataTable::new(
             "Gig",
             pool.clone(),
-            [Constraint::Length(15); 4].to_vec(),
-            vec!["Artist", "Venue", "Date", "Act"],
+            [Constraint::Length(15); 5].to_vec(),
+            vec!["Artist", "Venue", "Date", "Act", "Rating"],
         )
         .await?;
 
@@ -84,12 +120,38 @@ impl DataColumn<'_> {
         )
         .await?;
 
+        let festival_table = DataTable::new(
+            "Festival",
+            pool.clone(),
+            vec![
+                Constraint::Length(20),
+                Constraint::Length(20),
+                Constraint::Length(25),
+                Constraint::Fill(1),
+            ],
+            vec!["Name", "Site", "Dates", "Stages"],
+        )
+        .await?;
+
+        let person_table = DataTable::new(
+            "Person",
+            pool.clone(),
+            [Constraint::Length(30); 2].to_vec(),
+            vec!["Name", "Gigs"],
+        )
+        .await?;
+
+        let gig_detail = GigDetailPane::new(pool.clone()).await?;
+
         Ok(Self {
             is_focused: true,
             artist_table,
             venue_table,
             gig_table,
             city_table,
+            festival_table,
+            person_table,
+            gig_detail,
             focused_app: TableName::Artist,
         })
     }
@@ -99,10 +161,21 @@ impl DataColumn<'_> {
         self.venue_table.reload_data().await?;
         self.gig_table.reload_data().await?;
         self.city_table.reload_data().await?;
+        self.festival_table.reload_data().await?;
+        self.person_table.reload_data().await?;
+        self.gig_detail.reload_data().await?;
 
         Ok(())
     }
 
+    // Artists, venues and the rest are shared, only the gigs belong to a user. People are shared
+    // too, but only the user's gigs with them are counted once the tables reload
+    pub fn set_user(&mut self, user: Option<&User>) {
+        self.gig_table.set_scope(user::scope(user));
+        self.person_table.set_scope(user::scope(user));
+        self.gig_detail.set_user(user.cloned());
+    }
+
     pub fn focus(&mut self, new_focus: TableName) {
         self.is_focused = true;
 
@@ -111,6 +184,8 @@ impl DataColumn<'_> {
             TableName::Gig => self.gig_table.unfocus(),
             TableName::Venue => self.venue_table.unfocus(),
             TableName::City => self.city_table.unfocus(),
+            TableName::Festival => self.festival_table.unfocus(),
+            TableName::Person => self.person_table.unfocus(),
         }
 
         self.focused_app = new_focus;
@@ -120,6 +195,8 @@ impl DataColumn<'_> {
             TableName::Gig => self.gig_table.focus(),
             TableName::Venue => self.venue_table.focus(),
             TableName::City => self.city_table.focus(),
+            TableName::Festival => self.festival_table.focus(),
+            TableName::Person => self.person_table.focus(),
         }
     }
 
@@ -131,6 +208,8 @@ impl DataColumn<'_> {
             TableName::Gig => self.gig_table.focus(),
             TableName::Venue => self.venue_table.focus(),
             TableName::City => self.city_table.focus(),
+            TableName::Festival => self.festival_table.focus(),
+            TableName::Person => self.person_table.focus(),
         }
     }
 
@@ -147,16 +226,28 @@ impl DataColumn<'_> {
         let content_area = block.inner(area);
         frame.render_widget(block, area);
 
-        let [artist_area, venue_area, gig_area, city_area] =
-            Layout::vertical([Constraint::Fill(1); 4]).areas(content_area);
+        let [artist_area, venue_area, gig_area, city_area, festival_area, person_area] =
+            Layout::vertical([Constraint::Fill(1); 6]).areas(content_area);
 
         self.artist_table.render(frame, artist_area);
         self.venue_table.render(frame, venue_area);
-        self.gig_table.render(frame, gig_area);
+        // The selected gig's details sit beside the table while it has focus
+        if let TableName::Gig = self.focused_app {
+            let [table_area, detail_area] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(36)]).areas(gig_area);
+
+            self.gig_table.render(frame, table_area);
+            self.gig_detail
+                .render(frame, detail_area, self.gig_table.selected().as_ref());
+        } else {
+            self.gig_table.render(frame, gig_area);
+        }
         self.city_table.render(frame, city_area);
+        self.festival_table.render(frame, festival_area);
+        self.person_table.render(frame, person_area);
     }
 
-    pub fn handle_event(&mut self, event: Event) {
+    pub fn handle_event(&mut self, event: Event) -> Option<DataColumnEvent> {
         if let Event::Key(key) = event {
             match (key.modifiers, key.code) {
                 (KeyModifiers::CONTROL, KeyCode::Char('k')) => self.focus(self.focused_app.prev()),
@@ -166,10 +257,30 @@ impl DataColumn<'_> {
         }
 
         match self.focused_app {
-            TableName::Artist => self.artist_table.handle_event(event),
-            TableName::Venue => self.venue_table.handle_event(event),
-            TableName::City => self.city_table.handle_event(event),
-            TableName::Gig => self.gig_table.handle_event(event),
+            TableName::Artist => self
+                .artist_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(Record::Artist)),
+            TableName::Venue => self
+                .venue_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(Record::Venue)),
+            TableName::City => self
+                .city_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(Record::City)),
+            TableName::Gig => self
+                .gig_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(|gig| Record::Gig(Box::new(gig)))),
+            TableName::Festival => self
+                .festival_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(Record::Festival)),
+            TableName::Person => self
+                .person_table
+                .handle_event(event)
+                .map(|e| e.into_column_event(Record::Person)),
         }
     }
 }
@@ -1,14 +1,26 @@
 use crate::{
     dataset::DataSet,
-    date::{Month, MONTHS},
     error::Error,
     gig::Gig,
+    graphs::{
+        genres::GenresGraph,
+        heatmap::HeatmapGraph,
+        leaderboard::{Leaderboard, Ranking},
+        month::MonthGraph,
+        ratings::RatingsView,
+        songs::SongsView,
+        spend::SpendGraph,
+        year::YearGraph,
+        GraphView,
+    },
+    setlist::PlayedSong,
+    user::{self, User},
 };
+use crossterm::event::{Event, KeyCode};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Style, Stylize},
-    text::Line,
-    widgets::{Bar, BarChart, BarGroup, Block, BorderType},
+    widgets::{Block, BorderType, Borders, Tabs},
     Frame,
 };
 use sqlx::{Pool, Sqlite};
@@ -16,27 +28,64 @@ use sqlx::{Pool, Sqlite};
 pub struct GraphColumn {
     pool: Pool<Sqlite>,
     is_focused: bool,
-    gigs: Vec<Gig>,
+    // Only this user's gigs are graphed, everyone's when `None`
+    user: Option<User>,
+
+    views: Vec<Box<dyn GraphView>>,
+    current_view: usize,
 }
 
 impl GraphColumn {
     pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
-        let gigs = Gig::load_all(&pool).await?;
+        let views: Vec<Box<dyn GraphView>> = vec![
+            Box::new(MonthGraph::default()),
+            Box::new(YearGraph::default()),
+            Box::new(GenresGraph::default()),
+            Box::new(HeatmapGraph::default()),
+            Box::new(Leaderboard::new(Ranking::Artists)),
+            Box::new(Leaderboard::new(Ranking::Venues)),
+            Box::new(Leaderboard::new(Ranking::Cities)),
+            Box::new(Leaderboard::new(Ranking::Festivals)),
+            Box::new(Leaderboard::new(Ranking::People)),
+            Box::new(SpendGraph::default()),
+            Box::new(RatingsView::default()),
+            Box::new(SongsView::default()),
+        ];
 
-        Ok(Self {
+        let mut graph_column = Self {
             pool,
-            gigs,
             is_focused: false,
-        })
+            user: None,
+            views,
+            current_view: 0,
+        };
+
+        graph_column.reload_data().await?;
+
+        Ok(graph_column)
     }
 
     pub async fn reload_data(&mut self) -> Result<(), Error> {
-        let gigs = Gig::load_all(&self.pool).await?;
-        self.gigs = gigs;
+        let gigs = user::gigs_for(self.user.as_ref(), Gig::load_all(&self.pool).await?);
+        let songs: Vec<PlayedSong> = PlayedSong::load_all(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|song| gigs.iter().any(|gig| song.played_at(gig)))
+            .collect();
+
+        for view in self.views.iter_mut() {
+            view.compute(&gigs);
+            view.compute_songs(&songs);
+        }
 
         Ok(())
     }
 
+    // Takes effect on the next `reload_data`
+    pub fn set_user(&mut self, user: Option<User>) {
+        self.user = user;
+    }
+
     pub fn focus(&mut self) {
         self.is_focused = true;
     }
@@ -45,6 +94,25 @@ impl GraphColumn {
         self.is_focused = false;
     }
 
+    pub fn handle_event(&mut self, event: Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Tab => {
+                    self.current_view = (self.current_view + 1) % self.views.len();
+                    return;
+                }
+                KeyCode::BackTab => {
+                    self.current_view =
+                        (self.current_view + self.views.len() - 1) % self.views.len();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        self.views[self.current_view].handle_event(&event);
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         let border_style = Style::new().blue();
         let mut block = Block::bordered()
@@ -58,31 +126,16 @@ impl GraphColumn {
         let content_area = block.inner(area);
         frame.render_widget(block, area);
 
-        let dataset: Vec<Bar> = MONTHS
-            .iter()
-            .map(|month| {
-                let count =
-                    u64::try_from(self.gigs.iter().filter(|g| g.date.month.eq(month)).count())
-                        .expect("Value should be a valid u64");
+        let [tabs_area, view_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(content_area);
 
-                create_vertical_bar(month, count)
-            })
-            .collect();
+        let tabs = Tabs::new(self.views.iter().map(|view| view.title()))
+            .select(self.current_view)
+            .highlight_style(Style::new().yellow().bold())
+            .block(Block::new().borders(Borders::BOTTOM));
 
-        let chart = BarChart::default()
-            .bar_width(3)
-            .data(BarGroup::default().bars(&dataset))
-            .block(
-                Block::bordered()
-                    .title(Line::from("Gig Chart").white().bold().centered())
-                    .border_type(BorderType::Double)
-                    .border_style(Style::default().magenta()),
-            );
-
-        frame.render_widget(chart, content_area);
-    }
-}
+        frame.render_widget(tabs, tabs_area);
 
-fn create_vertical_bar<'a>(month: &Month, count: u64) -> Bar<'a> {
-    Bar::default().value(count).label(month.to_string().into())
+        self.views[self.current_view].render(frame, view_area);
+    }
 }
@@ -0,0 +1,93 @@
+use std::{env, fs, path::PathBuf, str::FromStr};
+
+use sqlx::{
+    error::BoxDynError,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteTypeInfo, SqliteValueRef},
+    Decode, Pool, Sqlite, Type,
+};
+
+use crate::error::Error;
+
+const APP_DIR: &str = "gig-tracker";
+const DB_FILE: &str = "gigs.db";
+
+// Opens the database pointed to by `DATABASE_URL`, falling back to the per-user data directory,
+// creating the file if it doesn't exist yet and bringing the schema up to date
+pub async fn connect() -> Result<Pool<Sqlite>, Error> {
+    let db_url = match env::var("DATABASE_URL") {
+        Ok(db_url) if !db_url.is_empty() => db_url,
+        _ => default_db_url()?,
+    };
+
+    let options = SqliteConnectOptions::from_str(&db_url)?.create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+    sqlx::migrate!().run(&pool).await?;
+
+    Ok(pool)
+}
+
+// The per-user directory the app keeps its files in, created if it doesn't exist yet
+pub fn data_dir() -> Result<PathBuf, Error> {
+    let data_dir = match env::var("XDG_DATA_HOME") {
+        Ok(data_home) if !data_home.is_empty() => PathBuf::from(data_home),
+        _ => {
+            let home = env::var("HOME")
+                .map_err(|_| Error::Str(String::from("Neither XDG_DATA_HOME or HOME are set")))?;
+
+            PathBuf::from(home).join(".local").join("share")
+        }
+    }
+    .join(APP_DIR);
+
+    fs::create_dir_all(&data_dir)?;
+
+    Ok(data_dir)
+}
+
+fn default_db_url() -> Result<String, Error> {
+    Ok(format!("sqlite:{}", data_dir()?.join(DB_FILE).display()))
+}
+
+// A list of names aggregated with `json_group_array`, so names are never split back apart
+pub struct Names(Vec<String>);
+
+impl Type<Sqlite> for Names {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <String as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Names {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw = <&str as Decode<Sqlite>>::decode(value)?;
+
+        Ok(Names(serde_json::from_str(raw)?))
+    }
+}
+
+impl From<Names> for Vec<String> {
+    fn from(names: Names) -> Self {
+        names.0
+    }
+}
+
+// A fresh in-memory database for tests, one connection keeps it alive for the whole test
+#[cfg(test)]
+pub async fn memory() -> Pool<Sqlite> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    sqlx::migrate!().run(&pool).await.unwrap();
+
+    pool
+}
@@ -1,10 +1,79 @@
+use std::{cmp::Ordering, fmt::Display};
+
 use sqlx::{Pool, Sqlite};
 
-use crate::error::Error;
+use crate::{error::Error, query::Filter};
+
+pub struct Dependent {
+    pub table: &'static str,
+    pub count: i64,
+}
+
+impl Display for Dependent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.count, self.table)
+    }
+}
+
+pub enum DeleteStrategy<T> {
+    // Refuse to delete while anything still references the row
+    Block,
+    // Delete everything that references the row first
+    Cascade,
+    // Point everything that references the row at another row first
+    Reassign(T),
+}
 
 pub trait DataSet: Sized + Clone + ToString {
+    // The `Filter` fields which can be used when searching this type's table
+    const FILTERS: &'static [&'static str] = &[];
+
     async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error>;
+
+    // Loads the rows as they're seen within a table's scope, for types with columns which count
+    // the active user's gigs
+    async fn load_scoped(pool: &Pool<Sqlite>, _scope: &[Filter]) -> Result<Vec<Self>, Error> {
+        Self::load_all(pool).await
+    }
     async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error>;
-    fn contains(&self, val: String) -> bool;
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error>;
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error>;
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error>;
+    // The values shown in each column of the record's table row
+    fn fields(&self) -> Vec<String>;
     fn key(&self) -> impl Ord + Clone;
+
+    fn matches(&self, _filter: &Filter) -> bool {
+        true
+    }
+
+    // Compares by the value shown in `column` of the record's table row
+    fn cmp_by(&self, other: &Self, _column: usize) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+
+    // Reads through the pool rather than a transaction, so it's called before a delete opens one
+    async fn ensure_unreferenced(&self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let dependents: Vec<String> = self
+            .dependents(pool)
+            .await?
+            .iter()
+            .filter(|dependent| dependent.count > 0)
+            .map(Dependent::to_string)
+            .collect();
+
+        if !dependents.is_empty() {
+            return Err(Error::Str(format!(
+                "\"{}\" is still referenced by {}",
+                self.to_string(),
+                dependents.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
 }
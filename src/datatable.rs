@@ -1,3 +1,5 @@
+use std::cmp::Reverse;
+
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
@@ -7,16 +9,42 @@ use ratatui::{
 };
 use sqlx::{Pool, Sqlite};
 
-use crate::{dataset::DataSet, error::Error};
+use crate::{
+    dataset::DataSet,
+    error::Error,
+    fuzzy::{fuzzy_match, highlight, FuzzyMatch},
+    query::{Filter, Query},
+};
+
+pub enum DataTableEvent<T> {
+    Open(T),
+    Edit(T),
+    Delete(T),
+}
+
+#[derive(Clone, Copy)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
 
-pub struct DataTable<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> {
+pub struct DataTable<'d, T: DataSet + Clone + Ord> {
     name: &'static str,
     searching: bool,
     search_text: String,
+    // The last query which parsed, kept while the search text has an error in it
+    query: Query,
+    search_error: Option<String>,
+    // Always applied on top of the search, e.g. to only show the active user's gigs
+    scope: Vec<Filter>,
 
     data: Vec<T>,
     filtered_data: Vec<T>,
 
+    headers: Vec<&'static str>,
+    // `None` sorts by `DataSet::key`
+    sort: Option<(usize, SortOrder)>,
+
     pool: Pool<Sqlite>,
     is_focused: bool,
     border_style: Style,
@@ -24,7 +52,7 @@ pub struct DataTable<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> {
     state: TableState,
 }
 
-impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
+impl<'d, T: DataSet + Clone + Ord> DataTable<'d, T> {
     pub async fn new(
         name: &'static str,
         pool: Pool<Sqlite>,
@@ -34,43 +62,51 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
         let mut data = T::load_all(&pool).await?;
         data.sort_unstable_by(|l, r| l.key().cmp(&r.key()));
 
-        let filtered_data = data.clone();
-
-        let table = Table::new(data.clone(), constraints)
-            .header(Row::new(headers))
+        let table = Table::new(Vec::<Row>::new(), constraints)
             .style(Style::new().white())
             .row_highlight_style(Style::new().dark_gray().on_gray());
 
-        Ok(Self {
+        let mut data_table = Self {
             name,
             pool,
             data,
-            filtered_data,
+            filtered_data: Vec::new(),
+            headers,
+            sort: None,
             searching: false,
             search_text: String::new(),
+            query: Query::default(),
+            search_error: None,
+            scope: Vec::new(),
             is_focused: false,
             border_style: Style::new().red(),
             table,
             state: TableState::default(),
-        })
+        };
+
+        data_table.update_filter();
+
+        Ok(data_table)
     }
 
     pub async fn reload_data(&mut self) -> Result<(), Error> {
-        let mut data = T::load_all(&self.pool).await?;
-        data.sort_unstable_by(|l, r| l.key().cmp(&r.key()));
-
-        self.data = data.clone();
-        self.filtered_data = data
-            .iter()
-            .filter(|row| row.contains(self.search_text.clone()))
-            .cloned()
-            .collect();
+        self.data = T::load_scoped(&self.pool, &self.scope).await?;
+        self.sort_data();
+        self.update_filter();
 
-        let rows: Vec<Row<'d>> = data.iter().map(|row| row.clone().into()).collect();
+        Ok(())
+    }
 
-        self.table = self.table.clone().rows(rows);
+    pub fn set_scope(&mut self, scope: Vec<Filter>) {
+        self.scope = scope;
+        self.update_filter();
+    }
 
-        Ok(())
+    pub fn selected(&self) -> Option<T> {
+        self.state
+            .selected()
+            .and_then(|idx| self.filtered_data.get(idx))
+            .cloned()
     }
 
     pub fn focus(&mut self) {
@@ -98,8 +134,19 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
                 Layout::vertical(vec![Constraint::Length(3), Constraint::Fill(1)])
                     .areas(content_area);
 
+            let mut search_block = Block::bordered().title("Search");
+
+            if let Some(err) = &self.search_error {
+                search_block = search_block
+                    .border_style(Style::new().red())
+                    .title(err.as_str());
+            } else if !T::FILTERS.is_empty() {
+                search_block = search_block
+                    .title_bottom(format!("Filter by {}", T::FILTERS.join(", ")).dark_gray());
+            }
+
             frame.render_widget(
-                Paragraph::new(self.search_text.clone()).block(Block::bordered().title("Search")),
+                Paragraph::new(self.search_text.clone()).block(search_block),
                 search_bar,
             );
             frame.render_stateful_widget(&self.table, list_view, &mut self.state);
@@ -108,7 +155,7 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
         }
     }
 
-    pub fn handle_event(&mut self, event: Event) {
+    pub fn handle_event(&mut self, event: Event) -> Option<DataTableEvent<T>> {
         if let Event::Key(key) = event {
             match (key.modifiers, key.code) {
                 (KeyModifiers::NONE, KeyCode::Backspace) => {
@@ -121,11 +168,15 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
                     self.update_filter();
                 }
 
-                (KeyModifiers::NONE, KeyCode::Enter) => {
+                (KeyModifiers::NONE, KeyCode::Enter) if self.searching => {
                     self.searching = false;
                 }
 
-                (KeyModifiers::NONE, KeyCode::Char('/')) => {
+                (KeyModifiers::NONE, KeyCode::Enter) => {
+                    return self.selected().map(DataTableEvent::Open);
+                }
+
+                (KeyModifiers::NONE, KeyCode::Char('/')) if !self.searching => {
                     self.searching = true;
                 }
 
@@ -133,7 +184,7 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
                     if self.searching {
                         self.search_text.push('k');
                         self.update_filter();
-                        return;
+                        return None;
                     }
 
                     self.state.select_previous();
@@ -143,38 +194,204 @@ impl<'d, T: Into<Row<'d>> + DataSet + Clone + Ord> DataTable<'d, T> {
                     if self.searching {
                         self.search_text.push('j');
                         self.update_filter();
-                        return;
+                        return None;
                     }
 
                     self.state.select_next();
                 }
 
-                (_, KeyCode::Char(char)) => {
-                    if self.searching {
-                        self.search_text.push(char);
-                        self.update_filter();
-                    }
+                (KeyModifiers::NONE, KeyCode::Char('e')) if !self.searching => {
+                    return self.selected().map(DataTableEvent::Edit);
+                }
+
+                (KeyModifiers::NONE, KeyCode::Char('d')) if !self.searching => {
+                    return self.selected().map(DataTableEvent::Delete);
+                }
+
+                (KeyModifiers::NONE, KeyCode::Char(digit @ '1'..='9')) if !self.searching => {
+                    self.toggle_sort(digit as usize - '1' as usize);
+                }
+
+                (_, KeyCode::Char(char)) if self.searching => {
+                    self.search_text.push(char);
+                    self.update_filter();
                 }
 
                 _ => {}
             }
         }
+
+        None
     }
 
     fn update_filter(&mut self) {
-        self.filtered_data = self
+        match self.parse_query() {
+            Ok(query) => {
+                self.query = query;
+                self.search_error = None;
+            }
+            Err(err) => self.search_error = Some(err),
+        }
+
+        let mut matches: Vec<(T, Vec<String>, FuzzyMatch)> = self
             .data
             .iter()
-            .filter(|row| row.contains(self.search_text.clone()))
-            .cloned()
+            .filter(|row| {
+                self.scope
+                    .iter()
+                    .chain(&self.query.filters)
+                    .all(|filter| row.matches(filter))
+            })
+            .filter_map(|row| {
+                let fields = row.fields();
+                let found = fuzzy_match(&self.query.text, &fields)?;
+
+                Some((row.clone(), fields, found))
+            })
             .collect();
 
-        let rows: Vec<Row<'d>> = self
-            .filtered_data
+        // Best matches first, equal scores stay in the sorted order
+        matches.sort_by_key(|(.., found)| Reverse(found.score));
+
+        let rows: Vec<Row<'d>> = matches
             .iter()
-            .map(|row| row.clone().into())
+            .map(|(_, fields, found)| {
+                Row::new(
+                    fields
+                        .iter()
+                        .zip(&found.indices)
+                        .map(|(field, indices)| highlight(field, indices)),
+                )
+            })
             .collect();
 
-        self.table = self.table.clone().rows(rows);
+        self.filtered_data = matches.into_iter().map(|(row, ..)| row).collect();
+        self.table = self.table.clone().header(self.header()).rows(rows);
+    }
+
+    fn parse_query(&self) -> Result<Query, String> {
+        let query: Query = self.search_text.parse()?;
+
+        if let Some(filter) = query
+            .filters
+            .iter()
+            .find(|filter| !T::FILTERS.contains(&filter.field()))
+        {
+            return Err(format!(
+                "{} can't be filtered by \"{}\"",
+                self.name,
+                filter.field()
+            ));
+        }
+
+        Ok(query)
+    }
+
+    // Each press on the same column goes ascending, descending, then back to the default order
+    fn toggle_sort(&mut self, column: usize) {
+        if column >= self.headers.len() {
+            return;
+        }
+
+        self.sort = match self.sort {
+            Some((current, SortOrder::Ascending)) if current == column => {
+                Some((column, SortOrder::Descending))
+            }
+            Some((current, SortOrder::Descending)) if current == column => None,
+            _ => Some((column, SortOrder::Ascending)),
+        };
+
+        self.sort_data();
+        self.update_filter();
+    }
+
+    fn sort_data(&mut self) {
+        match self.sort {
+            Some((column, SortOrder::Ascending)) => {
+                self.data.sort_by(|l, r| l.cmp_by(r, column));
+            }
+            Some((column, SortOrder::Descending)) => {
+                self.data.sort_by(|l, r| r.cmp_by(l, column));
+            }
+            None => self.data.sort_unstable_by(|l, r| l.key().cmp(&r.key())),
+        }
+    }
+
+    fn header(&self) -> Row<'d> {
+        Row::new(
+            self.headers
+                .iter()
+                .enumerate()
+                .map(|(idx, header)| match self.sort {
+                    Some((column, SortOrder::Ascending)) if column == idx => {
+                        format!("{} ▲", header)
+                    }
+                    Some((column, SortOrder::Descending)) if column == idx => {
+                        format!("{} ▼", header)
+                    }
+                    _ => header.to_string(),
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        act::Act, artist::Artist, city::City, database, date::Date, event::Event, gig::Gig,
+        venue::Venue,
+    };
+    use crossterm::event::KeyEvent;
+
+    async fn pool_with_gigs() -> Pool<Sqlite> {
+        let pool = database::memory().await;
+
+        City::save(City::new("Leeds".into()), &pool).await.unwrap();
+        let city_id = City::load_all(&pool).await.unwrap()[0].city_id;
+        Artist::save(Artist::new("Foals".into(), city_id), &pool)
+            .await
+            .unwrap();
+        let artist_id = Artist::load_all(&pool).await.unwrap()[0].artist_id;
+        Venue::save(Venue::new("Brudenell".into(), city_id), &pool)
+            .await
+            .unwrap();
+        let venue_id = Venue::load_all(&pool).await.unwrap()[0].venue_id;
+
+        for date in [Date::new(2021, 6, 1), Date::new(2023, 6, 1)] {
+            let gig = Gig::new(artist_id, venue_id, date.unwrap(), Act::Main);
+            Event::save_lineup(&gig, vec![(artist_id, Act::Main)], &pool)
+                .await
+                .unwrap();
+        }
+
+        pool
+    }
+
+    fn press(table: &mut DataTable<Gig>, code: KeyCode) {
+        table.handle_event(crossterm::event::Event::Key(KeyEvent::new(
+            code,
+            KeyModifiers::NONE,
+        )));
+    }
+
+    #[async_std::test]
+    async fn search_with_slash_date() {
+        let pool = pool_with_gigs().await;
+        let mut table = DataTable::<Gig>::new("Gigs", pool, vec![], vec!["Date"])
+            .await
+            .unwrap();
+
+        press(&mut table, KeyCode::Char('/'));
+        for char in "date>=2022/01/01".chars() {
+            press(&mut table, KeyCode::Char(char));
+        }
+
+        // The slashes in the date are typed into the search, not swallowed
+        assert!(table.searching);
+        assert_eq!(table.search_text, "date>=2022/01/01");
+        assert_eq!(table.search_error, None);
+        assert_eq!(table.filtered_data.len(), 1);
+        assert_eq!(table.filtered_data[0].date, Date::new(2023, 6, 1).unwrap());
     }
 }
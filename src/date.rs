@@ -1,8 +1,17 @@
-use std::{cmp::Ordering, fmt::Display};
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use crate::error::Error;
+use sqlx::{
+    error::BoxDynError,
+    sqlite::{SqliteTypeInfo, SqliteValueRef},
+    Decode, Sqlite, Type,
+};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Month {
     Unknown,
     Jan = 1,
@@ -34,6 +43,40 @@ pub const MONTHS: [Month; 12] = [
     Month::Dec,
 ];
 
+impl Month {
+    fn full_name(&self) -> &'static str {
+        match self {
+            Month::Unknown => "Unknown",
+            Month::Jan => "January",
+            Month::Feb => "February",
+            Month::Mar => "March",
+            Month::Apr => "April",
+            Month::May => "May",
+            Month::Jun => "June",
+            Month::Jul => "July",
+            Month::Aug => "August",
+            Month::Sep => "September",
+            Month::Oct => "October",
+            Month::Nov => "November",
+            Month::Dec => "December",
+        }
+    }
+
+    // Accepts both the short and full names, in any case, e.g. "jun", "Jun" or "June"
+    fn from_name(name: &str) -> Option<Month> {
+        let name = name.to_lowercase();
+
+        if name.len() < 3 {
+            return None;
+        }
+
+        MONTHS
+            .iter()
+            .find(|month| month.full_name().to_lowercase().starts_with(&name))
+            .copied()
+    }
+}
+
 impl From<u32> for Month {
     fn from(value: u32) -> Self {
         match value {
@@ -98,48 +141,247 @@ impl Display for Month {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Display for Weekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Weekday::Mon => "Mon",
+                Weekday::Tue => "Tue",
+                Weekday::Wed => "Wed",
+                Weekday::Thu => "Thu",
+                Weekday::Fri => "Fri",
+                Weekday::Sat => "Sat",
+                Weekday::Sun => "Sun",
+            }
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DateError {
+    Unrecognised(String),
+    OutOfRange { year: u32, month: u32, date: u32 },
+}
+
+impl std::error::Error for DateError {}
+
+impl Display for DateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateError::Unrecognised(raw) => write!(
+                f,
+                "\"{}\" isn't a date, try 2024/06/01, 2024-06-01, 01/06/2024 or 1 Jun 2024",
+                raw
+            ),
+            DateError::OutOfRange { year, month, date } => {
+                write!(f, "{:04}/{:02}/{:02} doesn't exist", year, month, date)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Date {
     pub date: u32,
     pub month: Month,
     pub year: u32,
 }
 
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Date {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.year > other.year {
-            return Ordering::Greater;
-        } else if self.year < other.year {
-            return Ordering::Less;
-        } else if self.month > other.month {
-            return Ordering::Greater;
-        } else if self.month < other.month {
-            return Ordering::Less;
-        } else if self.date > other.date {
-            return Ordering::Greater;
-        } else if self.date < other.date {
-            return Ordering::Less;
+        self.year
+            .cmp(&other.year)
+            .then(self.month.cmp(&other.month))
+            .then(self.date.cmp(&other.date))
+    }
+}
+
+impl Date {
+    pub fn new(year: u32, month: u32, date: u32) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) || date == 0 || date > days_in_month(year, month) {
+            return Err(DateError::OutOfRange { year, month, date });
+        }
+
+        Ok(Self {
+            date,
+            month: Month::from(month),
+            year,
+        })
+    }
+
+    // Keeps whatever numbers a stored date has without checking them, for dates saved before they
+    // were validated. Anything missing or unreadable is left as 0
+    fn unchecked(raw: &str) -> Self {
+        let mut components = raw
+            .trim()
+            .split(['/', '-'])
+            .map(|component| component.parse().unwrap_or_default());
+
+        let year = components.next().unwrap_or_default();
+        let month = components.next().unwrap_or_default();
+        let date = components.next().unwrap_or_default();
+
+        Self {
+            date,
+            month: Month::from(month),
+            year,
+        }
+    }
+
+    pub fn today() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / 86_400);
+
+        Self::from_days(days as i64)
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        match self.days().rem_euclid(7) {
+            // 1970/01/01 was a Thursday
+            0 => Weekday::Thu,
+            1 => Weekday::Fri,
+            2 => Weekday::Sat,
+            3 => Weekday::Sun,
+            4 => Weekday::Mon,
+            5 => Weekday::Tue,
+            _ => Weekday::Wed,
+        }
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+
+    // Moving from the 31st into a shorter month lands on the last day of that month
+    pub fn add_months(&self, months: i32) -> Self {
+        let total = self.year as i32 * 12 + u32::from(self.month) as i32 - 1 + months;
+        let year = total.div_euclid(12) as u32;
+        let month = total.rem_euclid(12) as u32 + 1;
+
+        Self {
+            date: self.date.min(days_in_month(year, month)),
+            month: Month::from(month),
+            year,
+        }
+    }
+
+    pub fn first_of_month(&self) -> Self {
+        Self {
+            date: 1,
+            month: self.month,
+            year: self.year,
         }
+    }
+
+    // Days since 1970/01/01, see http://howardhinnant.github.io/date_algorithms.html
+    fn days(&self) -> i64 {
+        let month = i64::from(u32::from(self.month));
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.date) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let date = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
 
-        Ordering::Equal
+        Self {
+            date: date as u32,
+            month: Month::from(month as u32),
+            year: year as u32,
+        }
     }
 }
 
-impl Date {
-    pub fn is_valid(raw_date: &str) -> bool {
-        let components: Vec<Result<u32, Error>> = raw_date
-            .split("/")
-            .map(|s| match s.parse::<u32>() {
-                Ok(val) => Ok(val),
-                Err(err) => Err(Error::Str(err.to_string())),
-            })
-            .collect();
+pub fn is_leap_year(year: u32) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+pub fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
 
-        if components.len() != 3 || components.iter().any(|c| c.is_err()) {
-            return false;
+    // Accepts `2024/06/01`, `2024-06-01`, `01/06/2024`, `1 Jun 2024`, `today` and `yesterday`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s.trim();
+        let unrecognised = || DateError::Unrecognised(raw.into());
+
+        match raw.to_lowercase().as_str() {
+            "today" => return Ok(Date::today()),
+            "yesterday" => return Ok(Date::today().add_days(-1)),
+            _ => {}
+        }
+
+        let words: Vec<&str> = raw.split_whitespace().collect();
+        if let [date, month, year] = words[..] {
+            let month = Month::from_name(month).ok_or_else(unrecognised)?;
+            let date = date.parse().map_err(|_| unrecognised())?;
+            let year = year.parse().map_err(|_| unrecognised())?;
+
+            return Date::new(year, u32::from(month), date);
         }
 
-        true
+        let components: Vec<&str> = raw.split(['/', '-']).collect();
+        let [first, month, last] = components[..] else {
+            return Err(unrecognised());
+        };
+
+        let month = month.parse().map_err(|_| unrecognised())?;
+        let first: u32 = first.parse().map_err(|_| unrecognised())?;
+        let last: u32 = last.parse().map_err(|_| unrecognised())?;
+
+        // Years are always written in full, so whichever end has 4 digits is the year
+        if components[0].len() == 4 {
+            Date::new(first, month, last)
+        } else if components[2].len() == 4 {
+            Date::new(last, month, first)
+        } else {
+            Err(unrecognised())
+        }
     }
 }
 
@@ -149,22 +391,173 @@ impl Display for Date {
             f,
             "{:04}/{:02}/{:02}",
             self.year,
-            u32::from(self.month.clone()),
+            u32::from(self.month),
             self.date
         )
     }
 }
 
-impl From<String> for Date {
-    fn from(value: String) -> Self {
-        let components: Vec<u32> = value.split("/").map(|s| s.parse().unwrap()).collect();
+// Dates are stored as `YYYY/MM/DD` text
+impl Type<Sqlite> for Date {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
 
-        assert!(components.len() == 3);
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <String as Type<Sqlite>>::compatible(ty)
+    }
+}
 
-        Self {
-            year: components[0],
-            month: Month::from(components[1]),
-            date: components[2],
+impl<'r> Decode<'r, Sqlite> for Date {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw = <&str as Decode<Sqlite>>::decode(value)?;
+
+        // One bad date shouldn't stop every gig from loading, it can be fixed from the gig form
+        Ok(raw.parse().unwrap_or_else(|_| Date::unchecked(raw)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u32, month: u32, date: u32) -> Date {
+        Date::new(year, month, date).unwrap()
+    }
+
+    #[test]
+    fn leap_days() {
+        assert!(Date::new(2024, 2, 29).is_ok());
+        assert!(Date::new(2000, 2, 29).is_ok());
+        assert_eq!(
+            Date::new(2023, 2, 29),
+            Err(DateError::OutOfRange {
+                year: 2023,
+                month: 2,
+                date: 29
+            })
+        );
+        assert!(Date::new(2100, 2, 29).is_err());
+        assert!(Date::new(2024, 2, 30).is_err());
+    }
+
+    #[test]
+    fn month_bounds() {
+        assert!(Date::new(2024, 1, 31).is_ok());
+        assert!(Date::new(2024, 4, 30).is_ok());
+        assert!(Date::new(2024, 4, 31).is_err());
+        assert!(Date::new(2024, 12, 31).is_ok());
+        assert!(Date::new(2024, 12, 32).is_err());
+        assert!(Date::new(2024, 6, 0).is_err());
+        assert!(Date::new(2024, 0, 1).is_err());
+        assert!(Date::new(2024, 13, 1).is_err());
+    }
+
+    #[test]
+    fn parses_each_format() {
+        let expected = date(2024, 6, 1);
+
+        for raw in [
+            "2024/06/01",
+            "2024-06-01",
+            "2024/6/1",
+            "01/06/2024",
+            "1-6-2024",
+            "1 Jun 2024",
+            "1 june 2024",
+            "01 JUNE 2024",
+            "  2024/06/01  ",
+        ] {
+            assert_eq!(raw.parse::<Date>(), Ok(expected.clone()), "{}", raw);
+        }
+
+        assert_eq!("yesterday".parse(), Ok(Date::today().add_days(-1)));
+        assert_eq!("Today".parse(), Ok(Date::today()));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        for raw in [
+            "",
+            "soon",
+            "2024/06",
+            "01/06/24",
+            "2024/06/01/02",
+            "1 Ju 2024",
+            "1 Jun",
+            "x/06/2024",
+        ] {
+            assert_eq!(
+                raw.parse::<Date>(),
+                Err(DateError::Unrecognised(raw.trim().into())),
+                "{}",
+                raw
+            );
+        }
+
+        assert!(matches!(
+            "2023/02/29".parse::<Date>(),
+            Err(DateError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            "31 Apr 2024".parse::<Date>(),
+            Err(DateError::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn displays_as_it_parses() {
+        let june = date(2024, 6, 1);
+
+        assert_eq!(june.to_string(), "2024/06/01");
+        assert_eq!(june.to_string().parse(), Ok(june));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(date(1970, 1, 1).weekday(), Weekday::Thu);
+        assert_eq!(date(1969, 12, 31).weekday(), Weekday::Wed);
+        assert_eq!(date(2000, 2, 29).weekday(), Weekday::Tue);
+        assert_eq!(date(2024, 6, 1).weekday(), Weekday::Sat);
+        assert_eq!(date(2024, 6, 3).weekday(), Weekday::Mon);
+    }
+
+    #[test]
+    fn days_round_trip() {
+        // Years are unsigned, so the earliest date there can be is 0001/01/01
+        for days in date(1, 1, 1).days()..date(3000, 1, 1).days() {
+            assert_eq!(Date::from_days(days).days(), days);
         }
+
+        assert_eq!(date(1970, 1, 1).days(), 0);
+        assert_eq!(Date::from_days(0), date(1970, 1, 1));
+    }
+
+    #[test]
+    fn adds_days() {
+        assert_eq!(date(2024, 2, 28).add_days(1), date(2024, 2, 29));
+        assert_eq!(date(2024, 2, 28).add_days(2), date(2024, 3, 1));
+        assert_eq!(date(2023, 2, 28).add_days(1), date(2023, 3, 1));
+        assert_eq!(date(2023, 12, 31).add_days(1), date(2024, 1, 1));
+        assert_eq!(date(2024, 1, 1).add_days(-1), date(2023, 12, 31));
+        assert_eq!(date(2024, 1, 1).add_days(366), date(2025, 1, 1));
+    }
+
+    #[test]
+    fn adds_months() {
+        assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+        assert_eq!(date(2023, 1, 31).add_months(1), date(2023, 2, 28));
+        assert_eq!(date(2024, 12, 15).add_months(1), date(2025, 1, 15));
+        assert_eq!(date(2024, 1, 15).add_months(-1), date(2023, 12, 15));
+        assert_eq!(date(2024, 3, 31).add_months(-13), date(2023, 2, 28));
+    }
+
+    #[test]
+    fn keeps_bad_stored_dates() {
+        let leap = Date::unchecked("2023/02/29");
+        assert_eq!((leap.year, leap.month, leap.date), (2023, Month::Feb, 29));
+        assert_eq!(leap.to_string(), "2023/02/29");
+
+        assert_eq!(Date::unchecked("June").to_string(), "0000/00/00");
     }
 }
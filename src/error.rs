@@ -6,7 +6,10 @@ use std::{
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
+    Csv(csv::Error),
+    Json(serde_json::Error),
     Sqlx(sqlx::Error),
+    Migrate(sqlx::migrate::MigrateError),
     Str(String),
 }
 
@@ -27,12 +30,30 @@ impl From<sqlx::Error> for Error {
     }
 }
 
+impl From<sqlx::migrate::MigrateError> for Error {
+    fn from(value: sqlx::migrate::MigrateError) -> Self {
+        Error::Migrate(value)
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(value: io::Error) -> Self {
         Error::Io(value)
     }
 }
 
+impl From<csv::Error> for Error {
+    fn from(value: csv::Error) -> Self {
+        Error::Csv(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value)
+    }
+}
+
 impl From<String> for Error {
     fn from(value: String) -> Self {
         Error::Str(value)
@@ -46,7 +67,10 @@ impl Display for Error {
             "{}",
             match self {
                 Error::Io(error) => error.to_string(),
+                Error::Csv(error) => error.to_string(),
+                Error::Json(error) => error.to_string(),
                 Error::Sqlx(error) => error.to_string(),
+                Error::Migrate(error) => error.to_string(),
                 Error::Str(error) => error.to_owned(),
             }
         )
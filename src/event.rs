@@ -0,0 +1,485 @@
+use std::collections::BTreeMap;
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{act::Act, dataset::DataSet, date::Date, error::Error, gig::Gig, setlist, user};
+
+// Every artist seen at a venue on one night, a gig is one slot in an event's lineup.
+// The venue and date are the same for every gig in the lineup
+pub struct Event {
+    pub event_id: i64,
+    // In running order
+    pub lineup: Vec<Gig>,
+}
+
+impl Event {
+    pub async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        let mut events: BTreeMap<i64, Event> = BTreeMap::new();
+
+        for gig in Gig::load_all(pool).await? {
+            events
+                .entry(gig.event_id)
+                .or_insert_with(|| Event {
+                    event_id: gig.event_id,
+                    lineup: Vec::new(),
+                })
+                .lineup
+                .push(gig);
+        }
+
+        let mut events: Vec<Event> = events.into_values().collect();
+        for event in events.iter_mut() {
+            event.lineup.sort_unstable_by_key(|gig| gig.position);
+        }
+
+        Ok(events)
+    }
+
+    // Adds every artist to the event at `gig`'s venue on its date in the order given, after anyone
+    // already on the lineup. The festival, stage, ticket, people, users, rating and notes are
+    // taken from `gig` too
+    pub async fn save_lineup(
+        gig: &Gig,
+        lineup: Vec<(i64, Act)>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        let event_id = find_or_create(&mut tx, gig.venue_id, &gig.date).await?;
+        add_details(&mut tx, event_id, gig).await?;
+
+        for (artist_id, act) in lineup {
+            let slot = Gig {
+                artist_id,
+                act,
+                ..gig.clone()
+            };
+
+            add_to_lineup(&mut tx, event_id, &slot).await?;
+            if !slot.users.is_empty() {
+                user::set_attended(&mut tx, event_id, &slot).await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+// Events are unique by venue and date, so there's only ever one to add artists to
+pub async fn find_or_create(
+    conn: &mut SqliteConnection,
+    venue_id: i64,
+    date: &Date,
+) -> Result<i64, Error> {
+    let existing: Option<i64> = sqlx::query_scalar(
+        r#"SELECT "event_id" FROM "event" WHERE "venue_id" = $1 AND "date" = $2"#,
+    )
+    .bind(venue_id)
+    .bind(date.to_string())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(event_id) = existing {
+        return Ok(event_id);
+    }
+
+    Ok(sqlx::query_scalar(
+        r#"INSERT INTO "event" ("venue_id", "date") VALUES ($1, $2) RETURNING "event_id""#,
+    )
+    .bind(venue_id)
+    .bind(date.to_string())
+    .fetch_one(conn)
+    .await?)
+}
+
+// Fills in the festival, ticket and people of an event from a gig joining its lineup, without
+// clearing what the rest of the lineup already has
+pub async fn add_details(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    gig: &Gig,
+) -> Result<(), Error> {
+    if gig.festival_id.is_some() {
+        set_festival(conn, event_id, gig.festival_id).await?;
+    }
+    if gig.has_ticket() {
+        set_ticket(conn, event_id, gig).await?;
+    }
+
+    add_people(conn, event_id, gig).await
+}
+
+pub async fn set_festival(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    festival_id: Option<i64>,
+) -> Result<(), Error> {
+    sqlx::query(r#"UPDATE "event" SET "festival_id" = $1 WHERE "event_id" = $2"#)
+        .bind(festival_id)
+        .bind(event_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn last_currency(pool: &Pool<Sqlite>) -> Result<Option<String>, Error> {
+    Ok(sqlx::query_scalar(
+        r#"
+        SELECT "currency" FROM "event" WHERE "currency" IS NOT NULL
+        ORDER BY "date" DESC, "event_id" DESC LIMIT 1"#,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn set_ticket(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    gig: &Gig,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE "event" SET "price" = $1, "fees" = $2, "currency" = $3, "ticket_type" = $4
+        WHERE "event_id" = $5"#,
+    )
+    .bind(gig.price)
+    .bind(gig.fees)
+    .bind(&gig.currency)
+    .bind(&gig.ticket_type)
+    .bind(event_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// Replaces everyone who came along to the event with `gig`'s people
+pub async fn set_people(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    gig: &Gig,
+) -> Result<(), Error> {
+    sqlx::query(r#"DELETE FROM "attendance" WHERE "event_id" = $1"#)
+        .bind(event_id)
+        .execute(&mut *conn)
+        .await?;
+
+    add_people(conn, event_id, gig).await
+}
+
+// Adds `gig`'s people to everyone who came along to the event
+async fn add_people(conn: &mut SqliteConnection, event_id: i64, gig: &Gig) -> Result<(), Error> {
+    for name in &gig.people {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO "attendance" ("event_id", "person_id")
+            SELECT $1, "person_id" FROM "person" WHERE "name" = $2"#,
+        )
+        .bind(event_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Puts `gig`'s artist at the end of the lineup, along with their stage, rating and notes
+pub async fn add_to_lineup(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    gig: &Gig,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO "gig" ("event_id", "artist_id", "act", "position", "stage_id", "rating", "notes")
+        VALUES ($1, $2, $3, (SELECT COALESCE(MAX("position") + 1, 0) FROM "gig" WHERE "event_id" = $1), $4, $5, $6)"#,
+    )
+    .bind(event_id)
+    .bind(gig.artist_id)
+    .bind(gig.act as i64)
+    .bind(gig.stage_id)
+    .bind(gig.rating)
+    .bind(&gig.notes)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// Folds the gig at `from` into the one at `to`, both given as (event_id, artist_id), for when
+// they turn out to be the same gig. The setlist goes after `to`'s, everyone who was at it is put
+// down as being at `to`, and its stage, rating and notes fill in any `to` is missing
+pub async fn merge_gig(
+    conn: &mut SqliteConnection,
+    from: (i64, i64),
+    to: (i64, i64),
+) -> Result<(), Error> {
+    setlist::append_songs(conn, from, to).await?;
+    user::move_attended(conn, from, to).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE "gig" SET
+            "stage_id" = COALESCE("gig"."stage_id", "from"."stage_id"),
+            "rating" = COALESCE("gig"."rating", "from"."rating"),
+            "notes" = COALESCE("gig"."notes", "from"."notes")
+        FROM "gig" "from"
+        WHERE "from"."event_id" = $1 AND "from"."artist_id" = $2
+          AND "gig"."event_id" = $3 AND "gig"."artist_id" = $4"#,
+    )
+    .bind(from.0)
+    .bind(from.1)
+    .bind(to.0)
+    .bind(to.1)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(r#"DELETE FROM "gig" WHERE "event_id" = $1 AND "artist_id" = $2"#)
+        .bind(from.0)
+        .bind(from.1)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+// Folds the event `from` into `to`, for when they turn out to be the same night. Artists on both
+// lineups are merged with `merge_gig`, the rest go after `to`'s lineup in their running order.
+// `to` keeps its own festival and ticket, only taking `from`'s when it has none
+pub async fn merge(conn: &mut SqliteConnection, from: i64, to: i64) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE "event" SET "festival_id" = "from"."festival_id"
+        FROM "event" "from"
+        WHERE "from"."event_id" = $1 AND "event"."event_id" = $2
+          AND "event"."festival_id" IS NULL"#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE "event" SET
+            "price" = "from"."price",
+            "fees" = "from"."fees",
+            "currency" = "from"."currency",
+            "ticket_type" = "from"."ticket_type"
+        FROM "event" "from"
+        WHERE "from"."event_id" = $1 AND "event"."event_id" = $2
+          AND "event"."price" IS NULL AND "event"."fees" IS NULL
+          AND "event"."ticket_type" IS NULL"#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO "attendance" ("event_id", "person_id")
+        SELECT $2, "person_id" FROM "attendance" WHERE "event_id" = $1"#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *conn)
+    .await?;
+
+    let shared: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT "artist_id" FROM "gig"
+        WHERE "event_id" = $1
+          AND "artist_id" IN (SELECT "artist_id" FROM "gig" WHERE "event_id" = $2)"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for artist_id in shared {
+        merge_gig(conn, (from, artist_id), (to, artist_id)).await?;
+    }
+
+    // Worked out up front, as the lineup grows while the gigs are moved
+    let offset: i64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(MAX("position") + 1, 0) FROM "gig" WHERE "event_id" = $1"#,
+    )
+    .bind(to)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE "gig" SET "event_id" = $1, "position" = "position" + $2
+        WHERE "event_id" = $3"#,
+    )
+    .bind(to)
+    .bind(offset)
+    .bind(from)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(r#"DELETE FROM "event" WHERE "event_id" = $1"#)
+        .bind(from)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+// An event with nobody on the lineup isn't worth keeping
+pub async fn remove_empty(conn: &mut SqliteConnection) -> Result<(), Error> {
+    sqlx::query(r#"DELETE FROM "event" WHERE "event_id" NOT IN (SELECT "event_id" FROM "gig")"#)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        artist::Artist,
+        city::City,
+        database,
+        dataset::DeleteStrategy,
+        setlist::{Setlist, Song},
+        user::User,
+        venue::Venue,
+    };
+
+    async fn gig(pool: &Pool<Sqlite>, artist_id: i64, venue_id: i64) -> Gig {
+        Gig::load_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|gig| gig.artist_id == artist_id && gig.venue_id == venue_id)
+            .unwrap()
+    }
+
+    async fn set_songs(pool: &Pool<Sqlite>, gig: &Gig, titles: &[&str]) {
+        Setlist {
+            event_id: gig.event_id,
+            artist_id: gig.artist_id,
+            songs: titles
+                .iter()
+                .map(|title| Song::new(title.to_string(), None, None))
+                .collect(),
+        }
+        .save(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn titles(pool: &Pool<Sqlite>, gig: &Gig) -> Vec<String> {
+        Setlist::load(gig, pool)
+            .await
+            .unwrap()
+            .songs
+            .into_iter()
+            .map(|song| song.title)
+            .collect()
+    }
+
+    // Reassigning onto an artist or venue already on the same night merges the gigs rather than
+    // clashing with the one already there
+    #[async_std::test]
+    async fn reassign_merges_shared_nights() {
+        let pool = database::memory().await;
+        let date = Date::new(2024, 5, 4).unwrap();
+
+        City::save(City::new("Leeds".into()), &pool).await.unwrap();
+        let city_id = City::load_all(&pool).await.unwrap()[0].city_id;
+        for name in ["Foals", "Foals (duplicate)"] {
+            Artist::save(Artist::new(name.into(), city_id), &pool)
+                .await
+                .unwrap();
+        }
+        for name in ["Brudenell", "Brudenell Social Club"] {
+            Venue::save(Venue::new(name.into(), city_id), &pool)
+                .await
+                .unwrap();
+        }
+        for name in ["Jo", "Kim"] {
+            User::save(User::new(name.into()), &pool).await.unwrap();
+        }
+
+        let artists = Artist::load_all(&pool).await.unwrap();
+        let (foals, duplicate) = (artists[0].clone(), artists[1].clone());
+        let venues = Venue::load_all(&pool).await.unwrap();
+        let (venue, other_venue) = (venues[0].clone(), venues[1].clone());
+        let users = User::load_all(&pool).await.unwrap();
+
+        let night = Gig::new(foals.artist_id, venue.venue_id, date.clone(), Act::Main);
+        Event::save_lineup(
+            &night.clone().with_users(vec![users[0].clone()]),
+            vec![(foals.artist_id, Act::Main)],
+            &pool,
+        )
+        .await
+        .unwrap();
+        // Only Kim put the duplicate down
+        Event::save_lineup(
+            &night.with_users(vec![users[1].clone()]),
+            vec![(duplicate.artist_id, Act::Main)],
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        let other_night = Gig::new(foals.artist_id, other_venue.venue_id, date, Act::Main)
+            .with_ticket(Some("20".into()), None, Some("GBP".into()), None)
+            .ok()
+            .unwrap();
+        Event::save_lineup(&other_night, vec![(foals.artist_id, Act::Main)], &pool)
+            .await
+            .unwrap();
+
+        set_songs(
+            &pool,
+            &gig(&pool, foals.artist_id, venue.venue_id).await,
+            &["Spanish Sahara"],
+        )
+        .await;
+        set_songs(
+            &pool,
+            &gig(&pool, duplicate.artist_id, venue.venue_id).await,
+            &["Inhaler"],
+        )
+        .await;
+        set_songs(
+            &pool,
+            &gig(&pool, foals.artist_id, other_venue.venue_id).await,
+            &["My Number"],
+        )
+        .await;
+
+        Artist::delete(duplicate, DeleteStrategy::Reassign(foals.clone()), &pool)
+            .await
+            .unwrap();
+
+        let merged = gig(&pool, foals.artist_id, venue.venue_id).await;
+        assert_eq!(titles(&pool, &merged).await, ["Spanish Sahara", "Inhaler"]);
+        assert_eq!(merged.users, ["Jo", "Kim"]);
+
+        Venue::delete(other_venue, DeleteStrategy::Reassign(venue.clone()), &pool)
+            .await
+            .unwrap();
+
+        let gigs = Gig::load_all(&pool).await.unwrap();
+        assert_eq!(gigs.len(), 1);
+        assert_eq!(
+            titles(&pool, &gigs[0]).await,
+            ["Spanish Sahara", "Inhaler", "My Number"]
+        );
+        // The ticket comes along as the kept night had none
+        assert_eq!(gigs[0].price, Some(2000));
+        assert_eq!(gigs[0].currency.as_deref(), Some("GBP"));
+    }
+}
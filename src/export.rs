@@ -0,0 +1,663 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::Write,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    artist::Artist, city::City, dataset::DataSet, error::Error, festival::Festival, gig::Gig,
+    person::Person, price, setlist::Setlist, user::User, venue::Venue,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct Export {
+    pub cities: Vec<CityExport>,
+    // Missing from exports made before festivals were tracked
+    #[serde(default)]
+    pub festivals: Vec<FestivalExport>,
+    // Each tagged artist's tags
+    #[serde(default)]
+    pub tags: BTreeMap<String, Vec<String>>,
+    // Everyone who has come along to a gig, or could
+    #[serde(default)]
+    pub people: Vec<String>,
+    #[serde(default)]
+    pub users: Vec<String>,
+    pub gigs: Vec<GigExport>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CityExport {
+    pub name: String,
+    pub artists: Vec<String>,
+    pub venues: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FestivalExport {
+    pub name: String,
+    pub venue: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub stages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GigExport {
+    pub artist: String,
+    pub venue: String,
+    pub date: String,
+    pub act: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub festival: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    // The night's ticket, repeated for each gig on its lineup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    // The setlist, in the order it was played
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub songs: Vec<SongExport>,
+    // Everyone who came along to the night
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<String>,
+    // The users who were at the gig
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SongExport {
+    pub title: String,
+    #[serde(default)]
+    pub encore: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl Export {
+    pub async fn load(pool: &Pool<Sqlite>) -> Result<Self, Error> {
+        let mut cities = City::load_all(pool).await?;
+        cities.sort_unstable();
+
+        let mut artists = Artist::load_all(pool).await?;
+        artists.sort_unstable();
+
+        let mut venues = Venue::load_all(pool).await?;
+        venues.sort_unstable();
+
+        let mut festivals = Festival::load_all(pool).await?;
+        festivals.sort_unstable();
+
+        let mut people = Person::load_all(pool).await?;
+        people.sort_unstable();
+
+        let mut users = User::load_all(pool).await?;
+        users.sort_unstable();
+
+        let mut gigs = Gig::load_all(pool).await?;
+        gigs.sort_unstable();
+
+        let tags = artists
+            .iter()
+            .filter(|artist| !artist.tags.is_empty())
+            .map(|artist| (artist.name.clone(), artist.tags.clone()))
+            .collect();
+
+        let cities = cities
+            .into_iter()
+            .map(|city| CityExport {
+                artists: artists
+                    .iter()
+                    .filter(|artist| artist.city_id == city.city_id)
+                    .map(|artist| artist.name.clone())
+                    .collect(),
+                venues: venues
+                    .iter()
+                    .filter(|venue| venue.city_id == city.city_id)
+                    .map(|venue| venue.name.clone())
+                    .collect(),
+                name: city.name,
+            })
+            .collect();
+
+        let festivals = festivals
+            .into_iter()
+            .map(|festival| FestivalExport {
+                stages: festival.stage_names(),
+                name: festival.name,
+                venue: festival.venue_name.unwrap_or_default(),
+                start_date: festival.start_date.to_string(),
+                end_date: festival.end_date.to_string(),
+            })
+            .collect();
+
+        let people = people.into_iter().map(|person| person.name).collect();
+        let users = users.into_iter().map(|user| user.name).collect();
+
+        let mut gig_exports = Vec::with_capacity(gigs.len());
+        for gig in gigs {
+            let songs = Setlist::load(&gig, pool)
+                .await?
+                .songs
+                .into_iter()
+                .map(|song| SongExport {
+                    title: song.title,
+                    encore: song.encore,
+                    cover_of: song.cover_of,
+                    notes: song.notes,
+                })
+                .collect();
+
+            gig_exports.push(GigExport {
+                people: gig.people,
+                users: gig.users,
+                artist: gig.artist_name.unwrap_or_default(),
+                venue: gig.venue_name.unwrap_or_default(),
+                date: gig.date.to_string(),
+                act: gig.act.to_string(),
+                festival: gig.festival_name,
+                stage: gig.stage_name,
+                price: gig.price.map(price::format),
+                fees: gig.fees.map(price::format),
+                currency: gig.currency,
+                ticket_type: gig.ticket_type,
+                rating: gig.rating,
+                notes: gig.notes,
+                songs,
+            });
+        }
+
+        Ok(Self {
+            cities,
+            festivals,
+            tags,
+            people,
+            users,
+            gigs: gig_exports,
+        })
+    }
+}
+
+pub async fn export_json(writer: impl Write, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    let export = Export::load(pool).await?;
+
+    serde_json::to_writer_pretty(writer, &export)?;
+
+    Ok(())
+}
+
+// Writes `cities.csv`, `artists.csv`, `venues.csv`, `festivals.csv`, `people.csv`, `users.csv`,
+// `songs.csv` and `gigs.csv` into `dir`. `gigs.csv` has the columns the CSV importer reads, so it
+// can be loaded straight back in.
+pub async fn export_csv(dir: &Path, pool: &Pool<Sqlite>) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut cities = City::load_all(pool).await?;
+    cities.sort_unstable();
+
+    let mut artists = Artist::load_all(pool).await?;
+    artists.sort_unstable();
+
+    let mut venues = Venue::load_all(pool).await?;
+    venues.sort_unstable();
+
+    let mut festivals = Festival::load_all(pool).await?;
+    festivals.sort_unstable();
+
+    let mut people = Person::load_all(pool).await?;
+    people.sort_unstable();
+
+    let mut users = User::load_all(pool).await?;
+    users.sort_unstable();
+
+    let mut gigs = Gig::load_all(pool).await?;
+    gigs.sort_unstable();
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("cities.csv"))?);
+    writer.write_record(["city_id", "name"])?;
+    for city in &cities {
+        writer.write_record([city.city_id.to_string(), city.name.clone()])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("artists.csv"))?);
+    writer.write_record(["artist_id", "name", "city", "tags"])?;
+    for artist in &artists {
+        writer.write_record([
+            artist.artist_id.to_string(),
+            artist.name.clone(),
+            artist.city_name.clone().unwrap_or_default(),
+            artist.tags.join(", "),
+        ])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("venues.csv"))?);
+    writer.write_record(["venue_id", "name", "city"])?;
+    for venue in &venues {
+        writer.write_record([
+            venue.venue_id.to_string(),
+            venue.name.clone(),
+            venue.city_name.clone().unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("festivals.csv"))?);
+    writer.write_record([
+        "festival_id",
+        "name",
+        "venue",
+        "start_date",
+        "end_date",
+        "stages",
+    ])?;
+    for festival in festivals {
+        writer.write_record([
+            festival.festival_id.to_string(),
+            festival.name,
+            festival.venue_name.unwrap_or_default(),
+            festival.start_date.to_string(),
+            festival.end_date.to_string(),
+            festival.stages,
+        ])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("people.csv"))?);
+    writer.write_record(["person_id", "name"])?;
+    for person in people {
+        writer.write_record([person.person_id.to_string(), person.name])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("users.csv"))?);
+    writer.write_record(["user_id", "name"])?;
+    for user in users {
+        writer.write_record([user.user_id.to_string(), user.name])?;
+    }
+    writer.flush()?;
+
+    let venue_cities: HashMap<i64, String> = venues
+        .into_iter()
+        .map(|venue| (venue.venue_id, venue.city_name.unwrap_or_default()))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("songs.csv"))?);
+    writer.write_record([
+        "artist", "venue", "date", "position", "title", "encore", "cover_of", "notes",
+    ])?;
+    for gig in &gigs {
+        for (position, song) in Setlist::load(gig, pool)
+            .await?
+            .songs
+            .into_iter()
+            .enumerate()
+        {
+            writer.write_record([
+                gig.artist_name.clone().unwrap_or_default(),
+                gig.venue_name.clone().unwrap_or_default(),
+                gig.date.to_string(),
+                position.to_string(),
+                song.title,
+                song.encore.to_string(),
+                song.cover_of.unwrap_or_default(),
+                song.notes.unwrap_or_default(),
+            ])?;
+        }
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_writer(File::create(dir.join("gigs.csv"))?);
+    writer.write_record([
+        "artist",
+        "venue",
+        "city",
+        "date",
+        "act",
+        "festival",
+        "stage",
+        "price",
+        "fees",
+        "currency",
+        "ticket_type",
+        "rating",
+        "notes",
+        "people",
+        "users",
+    ])?;
+    for gig in gigs {
+        writer.write_record([
+            gig.artist_name.unwrap_or_default(),
+            gig.venue_name.unwrap_or_default(),
+            venue_cities.get(&gig.venue_id).cloned().unwrap_or_default(),
+            gig.date.to_string(),
+            gig.act.to_string(),
+            gig.festival_name.unwrap_or_default(),
+            gig.stage_name.unwrap_or_default(),
+            gig.price.map(price::format).unwrap_or_default(),
+            gig.fees.map(price::format).unwrap_or_default(),
+            gig.currency.unwrap_or_default(),
+            gig.ticket_type.unwrap_or_default(),
+            gig.rating
+                .map(|rating| rating.to_string())
+                .unwrap_or_default(),
+            gig.notes.unwrap_or_default(),
+            gig.people.join(", "),
+            gig.users.join(", "),
+        ])?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        act::Act,
+        database,
+        date::Date,
+        event::Event,
+        festival::Stage,
+        import,
+        setlist::{Setlist, Song},
+    };
+
+    async fn seed(pool: &Pool<Sqlite>) {
+        City::save(City::new("Leeds".into()), pool).await.unwrap();
+        let city_id = City::load_all(pool).await.unwrap()[0].city_id;
+
+        Artist::save(
+            Artist::new("Wet Leg".into(), city_id)
+                .with_tags(vec!["indie".into(), "post-punk".into()]),
+            pool,
+        )
+        .await
+        .unwrap();
+        Artist::save(Artist::new("Foals".into(), city_id), pool)
+            .await
+            .unwrap();
+        Venue::save(Venue::new("Temple Newsam".into(), city_id), pool)
+            .await
+            .unwrap();
+
+        let artists = Artist::load_all(pool).await.unwrap();
+        let artist_id = |name: &str| {
+            artists
+                .iter()
+                .find(|artist| artist.name == name)
+                .unwrap()
+                .artist_id
+        };
+        let venue_id = Venue::load_all(pool).await.unwrap()[0].venue_id;
+
+        Festival::save(
+            Festival::new(
+                "Leeds Festival".into(),
+                venue_id,
+                Date::new(2024, 8, 23).unwrap(),
+                Date::new(2024, 8, 25).unwrap(),
+                vec!["Main Stage East".into(), "Festival Republic".into()],
+            ),
+            pool,
+        )
+        .await
+        .unwrap();
+
+        Person::save(Person::new("Alex".into()), pool)
+            .await
+            .unwrap();
+        // Never been to a gig, but still backed up
+        Person::save(Person::new("Sam".into()), pool).await.unwrap();
+        let alex = Person::load_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|person| person.name == "Alex")
+            .unwrap();
+
+        User::save(User::new("Jo".into()), pool).await.unwrap();
+        User::save(User::new("Kim".into()), pool).await.unwrap();
+        let jo = User::load_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|user| user.name == "Jo")
+            .unwrap();
+
+        let festival = Festival::load_all(pool).await.unwrap().remove(0);
+        let stage = Stage::load_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|stage| stage.name == "Festival Republic");
+
+        let gig = Gig::new(
+            artist_id("Wet Leg"),
+            venue_id,
+            Date::new(2024, 8, 24).unwrap(),
+            Act::Main,
+        )
+        .at_festival(Some(festival), stage)
+        .and_then(|gig| {
+            gig.with_ticket(
+                Some("75.50".into()),
+                Some("4.25".into()),
+                Some("gbp".into()),
+                Some("Weekend".into()),
+            )
+        })
+        .and_then(|gig| gig.with_review(Some(4), Some("Rained the whole set".into())))
+        .ok()
+        .unwrap()
+        .with_people(vec![alex])
+        .with_users(vec![jo]);
+
+        Event::save_lineup(
+            &gig,
+            vec![
+                (artist_id("Wet Leg"), Act::Main),
+                (artist_id("Foals"), Act::Support),
+            ],
+            pool,
+        )
+        .await
+        .unwrap();
+
+        Event::save_lineup(
+            &Gig::new(
+                artist_id("Foals"),
+                venue_id,
+                Date::new(2025, 1, 10).unwrap(),
+                Act::Main,
+            ),
+            vec![(artist_id("Foals"), Act::Main)],
+            pool,
+        )
+        .await
+        .unwrap();
+
+        let gig = Gig::load_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|gig| gig.artist_id == artist_id("Wet Leg"))
+            .unwrap();
+
+        Setlist {
+            event_id: gig.event_id,
+            artist_id: gig.artist_id,
+            songs: vec![
+                Song::new("Chaise Longue".into(), None, None),
+                Song {
+                    encore: true,
+                    ..Song::new(
+                        "Wish You Were Here".into(),
+                        Some("Pink Floyd".into()),
+                        Some("acoustic".into()),
+                    )
+                },
+            ],
+        }
+        .save(pool)
+        .await
+        .unwrap();
+    }
+
+    // Everything exported is restored by `import_json`, so exporting the restored database gives
+    // the same document back
+    #[async_std::test]
+    async fn json_round_trip() {
+        let pool = database::memory().await;
+        seed(&pool).await;
+
+        let mut exported = Vec::new();
+        export_json(&mut exported, &pool).await.unwrap();
+
+        let restored = database::memory().await;
+        let report = import::import_json(exported.as_slice(), None, &restored)
+            .await
+            .unwrap();
+        assert_eq!(report.imported, 3);
+        assert!(report.skipped.is_empty());
+
+        let mut reexported = Vec::new();
+        export_json(&mut reexported, &restored).await.unwrap();
+        assert_eq!(
+            String::from_utf8(exported).unwrap(),
+            String::from_utf8(reexported).unwrap()
+        );
+
+        let export = Export::load(&restored).await.unwrap();
+        assert_eq!(export.festivals.len(), 1);
+        assert_eq!(
+            export.festivals[0].stages,
+            ["Main Stage East", "Festival Republic"]
+        );
+
+        let gig = &export.gigs[0];
+        assert_eq!(gig.festival.as_deref(), Some("Leeds Festival"));
+        assert_eq!(gig.stage.as_deref(), Some("Festival Republic"));
+        assert_eq!(gig.price.as_deref(), Some("75.50"));
+        assert_eq!(gig.fees.as_deref(), Some("4.25"));
+        assert_eq!(gig.currency.as_deref(), Some("GBP"));
+        assert_eq!(gig.ticket_type.as_deref(), Some("Weekend"));
+        assert_eq!(gig.rating, Some(4));
+        assert_eq!(gig.notes.as_deref(), Some("Rained the whole set"));
+
+        let songs: Vec<(&str, bool)> = gig
+            .songs
+            .iter()
+            .map(|song| (song.title.as_str(), song.encore))
+            .collect();
+        assert_eq!(
+            songs,
+            [("Chaise Longue", false), ("Wish You Were Here", true)]
+        );
+        assert_eq!(gig.songs[1].cover_of.as_deref(), Some("Pink Floyd"));
+        assert_eq!(gig.songs[1].notes.as_deref(), Some("acoustic"));
+        assert_eq!(export.gigs[2].festival, None);
+
+        assert_eq!(export.people, ["Alex", "Sam"]);
+        assert_eq!(gig.people, ["Alex"]);
+        // Everyone sees the whole lineup
+        assert_eq!(export.gigs[1].people, ["Alex"]);
+        assert!(export.gigs[2].people.is_empty());
+
+        assert_eq!(export.tags.len(), 1);
+        assert_eq!(export.tags["Wet Leg"], ["indie", "post-punk"]);
+
+        assert_eq!(export.users, ["Jo", "Kim"]);
+        assert_eq!(gig.users, ["Jo"]);
+        assert!(export.gigs[2].users.is_empty());
+    }
+
+    // Each row of a CSV file, by column name
+    fn read_csv(path: &Path) -> Vec<HashMap<String, String>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_path(path)
+            .unwrap();
+        let headers = reader.headers().unwrap().clone();
+
+        reader
+            .records()
+            .map(|record| {
+                let record = record.unwrap();
+                assert_eq!(
+                    record.len(),
+                    headers.len(),
+                    "{} has a row that doesn't match its header",
+                    path.display()
+                );
+
+                headers
+                    .iter()
+                    .map(String::from)
+                    .zip(record.iter().map(String::from))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[async_std::test]
+    async fn csv_export() {
+        let pool = database::memory().await;
+        seed(&pool).await;
+
+        let dir = std::env::temp_dir().join(format!("gig-tracker-export-{}", std::process::id()));
+        export_csv(&dir, &pool).await.unwrap();
+
+        let artists = read_csv(&dir.join("artists.csv"));
+        let festivals = read_csv(&dir.join("festivals.csv"));
+        let people = read_csv(&dir.join("people.csv"));
+        let users = read_csv(&dir.join("users.csv"));
+        let gigs = read_csv(&dir.join("gigs.csv"));
+        let songs = read_csv(&dir.join("songs.csv"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(artists[1]["name"], "Wet Leg");
+        assert_eq!(artists[1]["tags"], "indie, post-punk");
+
+        assert_eq!(festivals.len(), 1);
+        assert_eq!(festivals[0]["stages"], "Main Stage East, Festival Republic");
+
+        assert_eq!(people.len(), 2);
+        assert_eq!(users.len(), 2);
+
+        assert_eq!(gigs.len(), 3);
+        assert_eq!(gigs[0]["festival"], "Leeds Festival");
+        assert_eq!(gigs[0]["stage"], "Festival Republic");
+        assert_eq!(gigs[0]["price"], "75.50");
+        assert_eq!(gigs[0]["currency"], "GBP");
+        assert_eq!(gigs[0]["rating"], "4");
+        assert_eq!(gigs[0]["people"], "Alex");
+        assert_eq!(gigs[0]["users"], "Jo");
+        assert_eq!(gigs[2]["festival"], "");
+        assert_eq!(gigs[2]["price"], "");
+
+        assert_eq!(songs.len(), 2);
+        assert_eq!(songs[1]["artist"], "Wet Leg");
+        assert_eq!(songs[1]["title"], "Wish You Were Here");
+        assert_eq!(songs[1]["encore"], "true");
+    }
+}
@@ -0,0 +1,573 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    date::Date,
+    error::Error,
+    event,
+    query::Filter,
+};
+
+// A festival runs over one or more days at a site, the gigs seen there are attached to it through
+// their event
+#[derive(Clone, PartialEq, Eq)]
+pub struct Festival {
+    pub festival_id: i64,
+    pub name: String,
+    pub venue_id: i64,
+    pub start_date: Date,
+    pub end_date: Date,
+    // Comma separated, in the order they were added
+    pub stages: String,
+
+    pub venue_name: Option<String>,
+    pub city_name: Option<String>,
+}
+
+impl PartialOrd for Festival {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Festival {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.start_date
+            .cmp(&other.start_date)
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+impl Festival {
+    pub fn new(
+        name: String,
+        venue_id: i64,
+        start_date: Date,
+        end_date: Date,
+        stages: Vec<String>,
+    ) -> Self {
+        Self {
+            festival_id: 0,
+            name,
+            venue_id,
+            start_date,
+            end_date,
+            stages: stages.join(", "),
+            venue_name: None,
+            city_name: None,
+        }
+    }
+
+    pub fn stage_names(&self) -> Vec<String> {
+        parse_stages(&self.stages)
+    }
+
+    pub fn dates(&self) -> String {
+        if self.start_date == self.end_date {
+            return self.start_date.to_string();
+        }
+
+        format!("{} - {}", self.start_date, self.end_date)
+    }
+
+    pub fn runs_on(&self, date: &Date) -> bool {
+        (&self.start_date..=&self.end_date).contains(&date)
+    }
+}
+
+// Stage names are entered as a comma separated list, blanks and repeats are dropped
+pub fn parse_stages(stages: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for name in stages.split(',').map(str::trim) {
+        if !name.is_empty() && !names.iter().any(|added| added.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+
+    names
+}
+
+impl DataSet for Festival {
+    const FILTERS: &'static [&'static str] = &["venue", "city", "year"];
+
+    async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            Festival,
+            r#"
+            SELECT 'f'.'festival_id', 'f'.'name', 'f'.'venue_id',
+                   'f'.'start_date' AS "start_date: Date", 'f'.'end_date' AS "end_date: Date",
+                   COALESCE((
+                       SELECT GROUP_CONCAT("name", ', ') FROM (
+                           SELECT "name" FROM "stage"
+                           WHERE "stage"."festival_id" = 'f'.'festival_id' ORDER BY "stage_id"
+                       )
+                   ), '') AS "stages!: String",
+                   'v'.'name' AS 'venue_name', 'c'.'name' AS 'city_name'
+            FROM 'festival' f
+            INNER JOIN 'venue' v ON 'v'.'venue_id' = 'f'.'venue_id'
+            INNER JOIN 'city' c ON 'c'.'city_id' = 'v'.'city_id'"#
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+
+    async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        let festival_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO "festival" ("name", "venue_id", "start_date", "end_date")
+            VALUES ($1, $2, $3, $4) RETURNING "festival_id""#,
+        )
+        .bind(&val.name)
+        .bind(val.venue_id)
+        .bind(val.start_date.to_string())
+        .bind(val.end_date.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        set_stages(&mut tx, festival_id, &val.stage_names()).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        ensure_fits(&mut tx, original.festival_id, &val).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE "festival" SET "name" = $1, "venue_id" = $2, "start_date" = $3, "end_date" = $4
+            WHERE "festival_id" = $5"#,
+        )
+        .bind(&val.name)
+        .bind(val.venue_id)
+        .bind(val.start_date.to_string())
+        .bind(val.end_date.to_string())
+        .bind(original.festival_id)
+        .execute(&mut *tx)
+        .await?;
+
+        set_stages(&mut tx, original.festival_id, &val.stage_names()).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            DeleteStrategy::Cascade => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM "gig"
+                    WHERE "event_id" IN (SELECT "event_id" FROM "event" WHERE "festival_id" = $1)"#,
+                )
+                .bind(val.festival_id)
+                .execute(&mut *tx)
+                .await?;
+
+                event::remove_empty(&mut tx).await?;
+            }
+            DeleteStrategy::Reassign(replacement) => {
+                ensure_fits(&mut tx, val.festival_id, &replacement).await?;
+
+                // Gigs keep their stage if the replacement has one with the same name
+                sqlx::query(
+                    r#"
+                    UPDATE "gig" SET "stage_id" = (
+                        SELECT "kept"."stage_id" FROM "stage" "old"
+                        INNER JOIN "stage" "kept" ON "kept"."name" = "old"."name"
+                        WHERE "old"."stage_id" = "gig"."stage_id" AND "kept"."festival_id" = $1
+                    )
+                    WHERE "stage_id" IN (SELECT "stage_id" FROM "stage" WHERE "festival_id" = $2)"#,
+                )
+                .bind(replacement.festival_id)
+                .bind(val.festival_id)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(r#"UPDATE "event" SET "festival_id" = $1 WHERE "festival_id" = $2"#)
+                    .bind(replacement.festival_id)
+                    .bind(val.festival_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        remove(&mut tx, val.festival_id).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let gigs: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM "gig"
+            INNER JOIN "event" ON "event"."event_id" = "gig"."event_id"
+            WHERE "event"."festival_id" = $1"#,
+        )
+        .bind(self.festival_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(vec![Dependent {
+            table: "gigs",
+            count: gigs,
+        }])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.venue_name.clone().unwrap_or_default(),
+            self.dates(),
+            self.stages.clone(),
+        ]
+    }
+
+    fn matches(&self, filter: &Filter) -> bool {
+        let contains = |name: &Option<String>, value: &String| {
+            name.as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(value))
+        };
+
+        match filter {
+            Filter::Venue(venue) => contains(&self.venue_name, venue),
+            Filter::City(city) => contains(&self.city_name, city),
+            Filter::Year(comparison, year) => comparison.compare(&self.start_date.year, year),
+            _ => true,
+        }
+    }
+
+    fn key(&self) -> impl Ord + Clone {
+        (&self.start_date, &self.name)
+    }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => self.name.cmp(&other.name),
+            1 => self.venue_name.cmp(&other.venue_name),
+            3 => self.stages.cmp(&other.stages),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
+}
+
+impl Display for Festival {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Stage {
+    pub stage_id: i64,
+    pub festival_id: i64,
+    pub name: String,
+
+    pub festival_name: Option<String>,
+}
+
+impl PartialOrd for Stage {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Stage {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.festival_name, &self.name).cmp(&(&other.festival_name, &other.name))
+    }
+}
+
+impl Stage {
+    // Stages are only ever changed through their festival
+    pub async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            Stage,
+            r#"
+            SELECT 's'.'stage_id', 's'.'festival_id', 's'.'name', 'f'.'name' AS 'festival_name'
+            FROM 'stage' s
+            INNER JOIN 'festival' f ON 'f'.'festival_id' = 's'.'festival_id'"#
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.festival_name {
+            Some(festival) => write!(f, "{} ({})", self.name, festival),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+// The nights already at the festival `festival_id` have to be at `festival`'s venue, on a day it
+// runs, before they can be moved to it or it can be changed to it
+async fn ensure_fits(
+    conn: &mut SqliteConnection,
+    festival_id: i64,
+    festival: &Festival,
+) -> Result<(), Error> {
+    let nights: Vec<(i64, Date)> =
+        sqlx::query_as(r#"SELECT "venue_id", "date" FROM "event" WHERE "festival_id" = $1"#)
+            .bind(festival_id)
+            .fetch_all(conn)
+            .await?;
+
+    let outside = nights
+        .iter()
+        .filter(|(venue_id, date)| *venue_id != festival.venue_id || !festival.runs_on(date))
+        .count();
+
+    if outside > 0 {
+        return Err(Error::Str(format!(
+            "{} nights at the festival would be outside {}'s venue and dates",
+            outside, festival.name
+        )));
+    }
+
+    Ok(())
+}
+
+// Adds any stages the festival doesn't have yet, and removes the ones not in `names`.
+// Names are matched ignoring case, so changing a stage's case renames it and its gigs keep it.
+// Gigs on a removed stage stay at the festival without a stage
+async fn set_stages(
+    conn: &mut SqliteConnection,
+    festival_id: i64,
+    names: &[String],
+) -> Result<(), Error> {
+    let mut existing: Vec<(i64, String)> = sqlx::query_as(
+        r#"SELECT "stage_id", "name" FROM "stage" WHERE "festival_id" = $1 ORDER BY "stage_id""#,
+    )
+    .bind(festival_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut kept: Vec<(i64, &String)> = Vec::new();
+
+    for name in names {
+        // An exact match first, so a rename never clashes with a stage that's already there
+        let found = existing
+            .iter()
+            .position(|(_, existing)| existing == name)
+            .or_else(|| {
+                existing
+                    .iter()
+                    .position(|(_, existing)| existing.eq_ignore_ascii_case(name))
+            });
+
+        let Some(idx) = found else {
+            sqlx::query(r#"INSERT INTO "stage" ("festival_id", "name") VALUES ($1, $2)"#)
+                .bind(festival_id)
+                .bind(name)
+                .execute(&mut *conn)
+                .await?;
+
+            continue;
+        };
+
+        let (stage_id, old_name) = existing.remove(idx);
+        if old_name != *name {
+            sqlx::query(r#"UPDATE "stage" SET "name" = $1 WHERE "stage_id" = $2"#)
+                .bind(name)
+                .bind(stage_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        kept.push((stage_id, name));
+    }
+
+    for (stage_id, name) in existing {
+        // Gigs on a repeat of a kept stage in another case move onto it, the rest lose their stage
+        let replacement = kept
+            .iter()
+            .find(|(_, kept)| kept.eq_ignore_ascii_case(&name))
+            .map(|(kept_id, _)| *kept_id);
+
+        sqlx::query(r#"UPDATE "gig" SET "stage_id" = $1 WHERE "stage_id" = $2"#)
+            .bind(replacement)
+            .bind(stage_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(r#"DELETE FROM "stage" WHERE "stage_id" = $1"#)
+            .bind(stage_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Festivals are unique by name and start date, an existing one is given any of `val`'s stages it
+// doesn't have yet
+pub async fn find_or_create(conn: &mut SqliteConnection, val: &Festival) -> Result<i64, Error> {
+    let existing: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT "festival_id" FROM "festival"
+        WHERE "name" = $1 COLLATE NOCASE AND "start_date" = $2"#,
+    )
+    .bind(&val.name)
+    .bind(val.start_date.to_string())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let festival_id = match existing {
+        Some(festival_id) => festival_id,
+        None => {
+            sqlx::query_scalar(
+                r#"
+                INSERT INTO "festival" ("name", "venue_id", "start_date", "end_date")
+                VALUES ($1, $2, $3, $4) RETURNING "festival_id""#,
+            )
+            .bind(&val.name)
+            .bind(val.venue_id)
+            .bind(val.start_date.to_string())
+            .bind(val.end_date.to_string())
+            .fetch_one(&mut *conn)
+            .await?
+        }
+    };
+
+    let mut names: Vec<String> = sqlx::query_scalar(
+        r#"SELECT "name" FROM "stage" WHERE "festival_id" = $1 ORDER BY "stage_id""#,
+    )
+    .bind(festival_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for name in val.stage_names() {
+        if !names.iter().any(|added| added.eq_ignore_ascii_case(&name)) {
+            names.push(name);
+        }
+    }
+
+    set_stages(conn, festival_id, &names).await?;
+
+    Ok(festival_id)
+}
+
+// Deletes a festival and its stages, the nights and gigs at it are kept without one
+async fn remove(conn: &mut SqliteConnection, festival_id: i64) -> Result<(), Error> {
+    set_stages(conn, festival_id, &[]).await?;
+
+    sqlx::query(r#"UPDATE "event" SET "festival_id" = NULL WHERE "festival_id" = $1"#)
+        .bind(festival_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(r#"DELETE FROM "festival" WHERE "festival_id" = $1"#)
+        .bind(festival_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+// Deletes every festival held at a venue that's being deleted
+pub async fn remove_at(conn: &mut SqliteConnection, venue_id: i64) -> Result<(), Error> {
+    let festivals: Vec<i64> =
+        sqlx::query_scalar(r#"SELECT "festival_id" FROM "festival" WHERE "venue_id" = $1"#)
+            .bind(venue_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    for festival_id in festivals {
+        remove(conn, festival_id).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        act::Act, artist::Artist, city::City, database, event::Event, gig::Gig, venue::Venue,
+    };
+
+    // Moving a festival's dates away from the gigs already at it is refused, rather than leaving
+    // them at a festival that wasn't on
+    #[async_std::test]
+    async fn attached_nights_stay_within_the_festival() {
+        let pool = database::memory().await;
+
+        City::save(City::new("Leeds".into()), &pool).await.unwrap();
+        let city_id = City::load_all(&pool).await.unwrap()[0].city_id;
+        Artist::save(Artist::new("Foals".into(), city_id), &pool)
+            .await
+            .unwrap();
+        let artist_id = Artist::load_all(&pool).await.unwrap()[0].artist_id;
+        Venue::save(Venue::new("Bramham Park".into(), city_id), &pool)
+            .await
+            .unwrap();
+        let venue_id = Venue::load_all(&pool).await.unwrap()[0].venue_id;
+
+        let festival = |start, end| {
+            Festival::new(
+                "Leeds Festival".into(),
+                venue_id,
+                Date::new(2024, 8, start).unwrap(),
+                Date::new(2024, 8, end).unwrap(),
+                Vec::new(),
+            )
+        };
+        Festival::save(festival(23, 25), &pool).await.unwrap();
+        Festival::save(festival(1, 2), &pool).await.unwrap();
+        let mut festivals = Festival::load_all(&pool).await.unwrap();
+        festivals.sort_unstable();
+        let (earlier, original) = (festivals[0].clone(), festivals[1].clone());
+
+        let gig = Gig::new(
+            artist_id,
+            venue_id,
+            Date::new(2024, 8, 24).unwrap(),
+            Act::Main,
+        )
+        .at_festival(Some(original.clone()), None)
+        .ok()
+        .unwrap();
+        Event::save_lineup(&gig, vec![(artist_id, Act::Main)], &pool)
+            .await
+            .unwrap();
+
+        assert!(Festival::update(&original, festival(25, 26), &pool)
+            .await
+            .is_err());
+        assert!(
+            Festival::delete(original.clone(), DeleteStrategy::Reassign(earlier), &pool)
+                .await
+                .is_err()
+        );
+
+        Festival::update(&original, festival(24, 26), &pool)
+            .await
+            .unwrap();
+    }
+}
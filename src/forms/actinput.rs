@@ -40,8 +40,13 @@ impl ActInput {
         self.focused = false;
     }
 
+    pub fn set_value(&mut self, act: Act) {
+        self.selected = Some(act);
+        self.current_tab = act;
+    }
+
     pub fn get_value(&self) -> Option<Act> {
-        self.selected.clone()
+        self.selected
     }
 
     pub fn set_err(&mut self, err: String) {
@@ -53,7 +58,7 @@ impl ActInput {
             match key.code {
                 KeyCode::Esc => return Some(ActInputEvent::Escape),
                 KeyCode::Enter => {
-                    self.selected = Some(self.current_tab.clone());
+                    self.selected = Some(self.current_tab);
                     return Some(ActInputEvent::Select);
                 }
                 KeyCode::Char('l') => {
@@ -81,7 +86,7 @@ impl ActInput {
             block = block.border_style(Style::new().red()).title_bottom(err);
         }
 
-        let tabs = Tabs::new(TAB_HEADERS).select(self.current_tab.clone());
+        let tabs = Tabs::new(TAB_HEADERS).select(self.current_tab);
 
         frame.render_widget(block, area);
         frame.render_widget(tabs, content_area);
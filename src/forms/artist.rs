@@ -5,51 +5,101 @@ use ratatui::{
 };
 use sqlx::{Pool, Sqlite};
 
-use crate::{artist::Artist, city::City, dataset::DataSet, error::Error};
+use crate::{artist::Artist, city::City, dataset::DataSet, error::Error, tag};
 
 use super::{
-    avfield::{AvField, AvFieldError},
+    avfield::AvFieldError,
     listinput::{ListInput, ListInputEvent},
     savebutton::{SaveButton, SaveButtonEvent},
+    taginput::{TagInput, TagInputEvent},
     textinput::{TextInput, TextInputEvent},
 };
 
+enum ArtistField {
+    None,
+    Name,
+    City,
+    Tags,
+    Save,
+}
+
+impl ArtistField {
+    fn next(&self) -> Self {
+        match self {
+            ArtistField::None => ArtistField::Name,
+            ArtistField::Name => ArtistField::City,
+            ArtistField::City => ArtistField::Tags,
+            ArtistField::Tags => ArtistField::Save,
+            ArtistField::Save => ArtistField::Save,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        match self {
+            ArtistField::None => ArtistField::None,
+            ArtistField::Name => ArtistField::None,
+            ArtistField::City => ArtistField::Name,
+            ArtistField::Tags => ArtistField::City,
+            ArtistField::Save => ArtistField::Tags,
+        }
+    }
+}
+
 pub struct ArtistForm<'a> {
     pool: Pool<Sqlite>,
-    current_field: AvField,
+    current_field: ArtistField,
+    editing: Option<Artist>,
 
     name: TextInput<'a>,
     city: ListInput<'a, City>,
+    tags: TagInput<'a>,
     save: SaveButton,
 }
 
 impl ArtistForm<'_> {
     pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
         let list_input = ListInput::new("City", &pool).await?;
+        let known_tags = tag::load_names(&pool).await?;
 
         Ok(Self {
             pool,
-            current_field: AvField::None,
+            current_field: ArtistField::None,
+            editing: None,
             name: TextInput::new("Name"),
             city: list_input,
+            tags: TagInput::new("Tags", known_tags),
             save: SaveButton::new(),
         })
     }
 
-    fn change_focus(&mut self, new_focus: AvField) {
+    pub async fn edit(pool: Pool<Sqlite>, artist: Artist) -> Result<Self, Error> {
+        let mut form = Self::new(pool).await?;
+
+        form.name.set_value(artist.name.clone());
+        form.city
+            .select_where(|city| city.city_id == artist.city_id);
+        form.tags.set_values(artist.tags.clone());
+        form.editing = Some(artist);
+
+        Ok(form)
+    }
+
+    fn change_focus(&mut self, new_focus: ArtistField) {
         match self.current_field {
-            AvField::Name => self.name.unfocus(),
-            AvField::City => self.city.unfocus(),
-            AvField::Save => self.save.unfocus(),
+            ArtistField::Name => self.name.unfocus(),
+            ArtistField::City => self.city.unfocus(),
+            ArtistField::Tags => self.tags.unfocus(),
+            ArtistField::Save => self.save.unfocus(),
             _ => {}
         }
 
         self.current_field = new_focus;
 
         match self.current_field {
-            AvField::Name => self.name.focus(),
-            AvField::City => self.city.focus(),
-            AvField::Save => self.save.focus(),
+            ArtistField::Name => self.name.focus(),
+            ArtistField::City => self.city.focus(),
+            ArtistField::Tags => self.tags.focus(),
+            ArtistField::Save => self.save.focus(),
             _ => {}
         }
     }
@@ -58,7 +108,7 @@ impl ArtistForm<'_> {
         if let Event::Key(key) = event {
             match (key.modifiers, key.code) {
                 (_, KeyCode::Enter) => {
-                    if let AvField::None = self.current_field {
+                    if let ArtistField::None = self.current_field {
                         self.change_focus(self.current_field.next());
                         return Ok(false);
                     }
@@ -77,7 +127,7 @@ impl ArtistForm<'_> {
         }
 
         match self.current_field {
-            AvField::Name => {
+            ArtistField::Name => {
                 if let Some(input_event) = self.name.handle_event(&event) {
                     match input_event {
                         TextInputEvent::Escape => self.name.unfocus(),
@@ -88,7 +138,7 @@ impl ArtistForm<'_> {
                 }
             }
 
-            AvField::City => {
+            ArtistField::City => {
                 if let Some(list_event) = self.city.handle_event(&event) {
                     match list_event {
                         ListInputEvent::Escape => self.city.unfocus(),
@@ -99,7 +149,18 @@ impl ArtistForm<'_> {
                 }
             }
 
-            AvField::Save => {
+            ArtistField::Tags => {
+                if let Some(input_event) = self.tags.handle_event(&event) {
+                    match input_event {
+                        TagInputEvent::Escape => self.tags.unfocus(),
+                        TagInputEvent::Save => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+
+            ArtistField::Save => {
                 if let Some(save_event) = self.save.handle_event(&event) {
                     match save_event {
                         SaveButtonEvent::Escape => self.save.unfocus(),
@@ -131,15 +192,17 @@ impl ArtistForm<'_> {
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        let [top, middle, bottom] = Layout::vertical(vec![
+        let [top, middle, tags, bottom] = Layout::vertical(vec![
             Constraint::Length(3),
             Constraint::Fill(1),
             Constraint::Length(3),
+            Constraint::Length(3),
         ])
         .areas(area);
 
         self.name.render(frame, top);
         self.city.render(frame, middle);
+        self.tags.render(frame, tags);
         self.save.render(frame, bottom);
     }
 
@@ -163,9 +226,14 @@ impl ArtistForm<'_> {
             }
         };
 
-        let artist = Artist::new(artist_name, city.city_id);
+        let artist = Artist::new(artist_name, city.city_id).with_tags(self.tags.get_values());
+
+        let result = match &self.editing {
+            Some(original) => Artist::update(original, artist, &self.pool).await,
+            None => Artist::save(artist, &self.pool).await,
+        };
 
-        match Artist::save(artist, &self.pool).await {
+        match result {
             Ok(_) => Ok(None),
             Err(err) => Ok(Some(AvFieldError::Save(err.to_string()))),
         }
@@ -44,6 +44,7 @@ impl CityField {
 pub struct CityForm<'a> {
     pool: Pool<Sqlite>,
     current_field: CityField,
+    editing: Option<City>,
     name: TextInput<'a>,
     save: SaveButton,
 }
@@ -53,11 +54,21 @@ impl CityForm<'_> {
         Self {
             pool,
             current_field: CityField::None,
+            editing: None,
             name: TextInput::new("Name"),
             save: SaveButton::new(),
         }
     }
 
+    pub fn edit(pool: Pool<Sqlite>, city: City) -> Self {
+        let mut form = Self::new(pool);
+
+        form.name.set_value(city.name.clone());
+        form.editing = Some(city);
+
+        form
+    }
+
     fn change_focus(&mut self, new_focus: CityField) {
         match self.current_field {
             CityField::Name => self.name.unfocus(),
@@ -151,7 +162,12 @@ impl CityForm<'_> {
 
         let city = City::new(city_name);
 
-        match City::save(city, &self.pool).await {
+        let result = match &self.editing {
+            Some(original) => City::update(original, city, &self.pool).await,
+            None => City::save(city, &self.pool).await,
+        };
+
+        match result {
             Ok(_) => Ok(None),
             Err(err) => Ok(Some(CityFieldError::Save(err.to_string()))),
         }
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use crate::{
+    dataset::DataSet,
+    date::{days_in_month, Date},
+    error::Error,
+    gig::Gig,
+};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+const WEEKDAY_HEADERS: &str = "Mo Tu We Th Fr Sa Su";
+
+pub enum DateInputEvent {
+    Escape,
+    Select,
+}
+
+pub struct DateInput<'a> {
+    title: &'a str,
+    focused: bool,
+    selected: Option<Date>,
+    error: Option<String>,
+
+    cursor: Date,
+    gig_dates: HashSet<Date>,
+}
+
+impl<'a> DateInput<'a> {
+    pub async fn new(title: &'a str, pool: &Pool<Sqlite>) -> Result<Self, Error> {
+        let gig_dates = Gig::load_all(pool)
+            .await?
+            .into_iter()
+            .map(|gig| gig.date)
+            .collect();
+
+        Ok(Self {
+            title,
+            focused: false,
+            selected: None,
+            error: None,
+            cursor: Date::today(),
+            gig_dates,
+        })
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn set_value(&mut self, date: Date) {
+        self.cursor = date.clone();
+        self.selected = Some(date);
+    }
+
+    // Moves the calendar to `date` without selecting it
+    pub fn set_cursor(&mut self, date: Date) {
+        self.cursor = date;
+    }
+
+    pub fn get_value(&self) -> Option<Date> {
+        self.selected.clone()
+    }
+
+    pub fn set_err(&mut self, err: String) {
+        self.error = Some(err);
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<DateInputEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(DateInputEvent::Escape),
+                KeyCode::Enter => {
+                    self.selected = Some(self.cursor.clone());
+                    return Some(DateInputEvent::Select);
+                }
+                KeyCode::Char('h') => self.cursor = self.cursor.add_days(-1),
+                KeyCode::Char('l') => self.cursor = self.cursor.add_days(1),
+                KeyCode::Char('k') => self.cursor = self.cursor.add_days(-7),
+                KeyCode::Char('j') => self.cursor = self.cursor.add_days(7),
+                KeyCode::Char('H') => self.cursor = self.cursor.add_months(-1),
+                KeyCode::Char('L') => self.cursor = self.cursor.add_months(1),
+                KeyCode::Char('K') => self.cursor = self.cursor.add_months(-12),
+                KeyCode::Char('J') => self.cursor = self.cursor.add_months(12),
+                KeyCode::Char('t') => self.cursor = Date::today(),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title_top(self.title);
+
+        if self.focused {
+            block = block
+                .border_type(BorderType::Double)
+                .title_bottom("H/L: Month J/K: Year");
+        }
+
+        if let Some(err) = self.error.clone() {
+            block = block.border_style(Style::new().red()).title_bottom(err);
+        }
+
+        let mut lines = vec![
+            Line::from(format!("{} {}", self.cursor.month, self.cursor.year))
+                .bold()
+                .centered(),
+            Line::from(WEEKDAY_HEADERS).dark_gray(),
+        ];
+
+        let first = self.cursor.first_of_month();
+        let mut weekday = first.weekday() as usize;
+        let mut week = vec![Span::raw("   "); weekday];
+
+        for day in 1..=days_in_month(first.year, u32::from(first.month)) {
+            let date = first.add_days(i64::from(day) - 1);
+
+            // The gap between days is left unstyled so highlights don't run together
+            week.push(self.day_span(&date));
+            week.push(Span::raw(" "));
+            weekday += 1;
+
+            if weekday == 7 {
+                lines.push(Line::from(std::mem::take(&mut week)));
+                weekday = 0;
+            }
+        }
+
+        if !week.is_empty() {
+            lines.push(Line::from(week));
+        }
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn day_span(&self, date: &Date) -> Span<'static> {
+        let mut style = Style::new();
+
+        if self.gig_dates.contains(date) {
+            style = style.magenta().bold();
+        }
+
+        if self.selected.as_ref() == Some(date) {
+            style = style.green().underlined();
+        }
+
+        if self.focused && &self.cursor == date {
+            style = style.reversed();
+        }
+
+        Span::styled(format!("{:>2}", date.date), style)
+    }
+}
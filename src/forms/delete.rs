@@ -0,0 +1,216 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, List, ListState, Tabs},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    dataset::{DeleteStrategy, Dependent},
+    error::Error,
+    record::Record,
+};
+
+const STRATEGY_HEADERS: [&str; 3] = ["Block", "Cascade", "Reassign"];
+
+pub enum DeleteFormEvent {
+    Cancel,
+    Deleted,
+}
+
+#[derive(Clone, Copy)]
+enum Strategy {
+    Block = 0,
+    Cascade,
+    Reassign,
+}
+
+impl Strategy {
+    fn next(&self) -> Self {
+        match self {
+            Strategy::Block => Strategy::Cascade,
+            Strategy::Cascade => Strategy::Reassign,
+            Strategy::Reassign => Strategy::Reassign,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        match self {
+            Strategy::Block => Strategy::Block,
+            Strategy::Cascade => Strategy::Block,
+            Strategy::Reassign => Strategy::Cascade,
+        }
+    }
+}
+
+impl From<Strategy> for Option<usize> {
+    fn from(value: Strategy) -> Self {
+        Some(value as usize)
+    }
+}
+
+pub struct DeleteForm {
+    pool: Pool<Sqlite>,
+    record: Record,
+    dependents: Vec<Dependent>,
+    error: Option<String>,
+
+    strategy: Strategy,
+    replacements: Vec<Record>,
+    state: ListState,
+}
+
+impl DeleteForm {
+    pub async fn new(pool: Pool<Sqlite>, record: Record) -> Result<Self, Error> {
+        let dependents: Vec<Dependent> = record
+            .dependents(&pool)
+            .await?
+            .into_iter()
+            .filter(|dependent| dependent.count > 0)
+            .collect();
+
+        let replacements = record.siblings(&pool).await?;
+
+        Ok(Self {
+            pool,
+            record,
+            dependents,
+            error: None,
+            strategy: Strategy::Block,
+            replacements,
+            state: ListState::default().with_selected(Some(0)),
+        })
+    }
+
+    pub async fn handle_event(&mut self, event: &Event) -> Result<Option<DeleteFormEvent>, Error> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Ok(Some(DeleteFormEvent::Cancel)),
+                KeyCode::Enter => {
+                    return match self.delete().await {
+                        Ok(_) => Ok(Some(DeleteFormEvent::Deleted)),
+                        Err(err) => {
+                            self.error = Some(err.to_string());
+                            Ok(None)
+                        }
+                    };
+                }
+                KeyCode::Char('l') if !self.dependents.is_empty() => {
+                    self.strategy = self.strategy.next();
+                }
+                KeyCode::Char('h') if !self.dependents.is_empty() => {
+                    self.strategy = self.strategy.prev();
+                }
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn delete(&self) -> Result<(), Error> {
+        let strategy = match self.strategy {
+            Strategy::Block => DeleteStrategy::Block,
+            Strategy::Cascade => DeleteStrategy::Cascade,
+            Strategy::Reassign => {
+                let replacement = self
+                    .state
+                    .selected()
+                    .and_then(|idx| self.replacements.get(idx))
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::Str(format!("No other {} to reassign to", self.record.kind()))
+                    })?;
+
+                DeleteStrategy::Reassign(replacement)
+            }
+        };
+
+        self.record.clone().delete(strategy, &self.pool).await
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, mid_col, _] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, mid_area, _] = Layout::vertical(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ])
+        .areas(mid_col);
+
+        let mut block = Block::bordered()
+            .border_type(BorderType::Double)
+            .white()
+            .title(format!("Delete {} \"{}\"", self.record.kind(), self.record))
+            .title_bottom("Enter: Confirm / Esc: Cancel");
+
+        if let Some(err) = self.error.clone() {
+            block = block.border_style(Style::new().red()).title_bottom(err);
+        }
+
+        let content_area = block.inner(mid_area);
+
+        frame.render_widget(Clear {}, mid_area);
+        frame.render_widget(block, mid_area);
+
+        if self.dependents.is_empty() {
+            frame.render_widget(
+                Text::from("Nothing depends on this record, it can be safely deleted"),
+                content_area,
+            );
+            return;
+        }
+
+        let dependents: Vec<String> = self.dependents.iter().map(Dependent::to_string).collect();
+
+        let [summary_area, strategy_area, detail_area] = Layout::vertical(vec![
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .areas(content_area);
+
+        frame.render_widget(
+            Line::from(format!(
+                "Deleting this would orphan {}",
+                dependents.join(", ")
+            )),
+            summary_area,
+        );
+
+        frame.render_widget(
+            Tabs::new(STRATEGY_HEADERS)
+                .select(self.strategy)
+                .block(Block::new().borders(Borders::BOTTOM)),
+            strategy_area,
+        );
+
+        match self.strategy {
+            Strategy::Block => frame.render_widget(
+                Text::from("Only delete if nothing depends on this record"),
+                detail_area,
+            ),
+            Strategy::Cascade => frame.render_widget(
+                Text::from(format!("Also delete {}", dependents.join(", "))).red(),
+                detail_area,
+            ),
+            Strategy::Reassign => {
+                let list = List::new(self.replacements.iter().map(Record::to_string))
+                    .block(Block::bordered().title("Reassign To"))
+                    .highlight_style(Style::new().on_gray());
+
+                frame.render_stateful_widget(list, detail_area, &mut self.state);
+            }
+        }
+    }
+}
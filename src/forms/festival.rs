@@ -0,0 +1,301 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    dataset::DataSet,
+    error::Error,
+    festival::{parse_stages, Festival},
+    venue::Venue,
+};
+
+use super::{
+    dateinput::{DateInput, DateInputEvent},
+    listinput::{ListInput, ListInputEvent},
+    savebutton::{SaveButton, SaveButtonEvent},
+    textinput::{TextInput, TextInputEvent},
+};
+
+enum FestivalField {
+    None,
+    Name,
+    Site,
+    Start,
+    End,
+    Stages,
+    Save,
+}
+
+impl FestivalField {
+    fn next(&self) -> Self {
+        match self {
+            FestivalField::None => FestivalField::Name,
+            FestivalField::Name => FestivalField::Site,
+            FestivalField::Site => FestivalField::Start,
+            FestivalField::Start => FestivalField::End,
+            FestivalField::End => FestivalField::Stages,
+            FestivalField::Stages => FestivalField::Save,
+            FestivalField::Save => FestivalField::Save,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        match self {
+            FestivalField::None => FestivalField::None,
+            FestivalField::Name => FestivalField::None,
+            FestivalField::Site => FestivalField::Name,
+            FestivalField::Start => FestivalField::Site,
+            FestivalField::End => FestivalField::Start,
+            FestivalField::Stages => FestivalField::End,
+            FestivalField::Save => FestivalField::Stages,
+        }
+    }
+}
+
+enum FestivalFieldError {
+    Name(String),
+    Site(String),
+    Start(String),
+    End(String),
+    Save(String),
+}
+
+pub struct FestivalForm<'a> {
+    pool: Pool<Sqlite>,
+    current_field: FestivalField,
+    editing: Option<Festival>,
+
+    name: TextInput<'a>,
+    site: ListInput<'a, Venue>,
+    start: DateInput<'a>,
+    end: DateInput<'a>,
+    // Comma separated
+    stages: TextInput<'a>,
+    save: SaveButton,
+}
+
+impl FestivalForm<'_> {
+    pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
+        let site = ListInput::new("Site", &pool).await?;
+        let start = DateInput::new("Start", &pool).await?;
+        let end = DateInput::new("End", &pool).await?;
+
+        Ok(Self {
+            pool,
+            current_field: FestivalField::None,
+            editing: None,
+            name: TextInput::new("Name"),
+            site,
+            start,
+            end,
+            stages: TextInput::new("Stages (comma separated)"),
+            save: SaveButton::new(),
+        })
+    }
+
+    pub async fn edit(pool: Pool<Sqlite>, festival: Festival) -> Result<Self, Error> {
+        let mut form = Self::new(pool).await?;
+
+        form.name.set_value(festival.name.clone());
+        form.site
+            .select_where(|venue| venue.venue_id == festival.venue_id);
+        form.start.set_value(festival.start_date.clone());
+        form.end.set_value(festival.end_date.clone());
+        form.stages.set_value(festival.stages.clone());
+        form.editing = Some(festival);
+
+        Ok(form)
+    }
+
+    fn change_focus(&mut self, new_focus: FestivalField) {
+        match self.current_field {
+            FestivalField::Name => self.name.unfocus(),
+            FestivalField::Site => self.site.unfocus(),
+            FestivalField::Start => self.start.unfocus(),
+            FestivalField::End => self.end.unfocus(),
+            FestivalField::Stages => self.stages.unfocus(),
+            FestivalField::Save => self.save.unfocus(),
+            _ => {}
+        }
+
+        self.current_field = new_focus;
+
+        match self.current_field {
+            FestivalField::Name => self.name.focus(),
+            FestivalField::Site => self.site.focus(),
+            FestivalField::Start => self.start.focus(),
+            FestivalField::End => self.end.focus(),
+            FestivalField::Stages => self.stages.focus(),
+            FestivalField::Save => self.save.focus(),
+            _ => {}
+        }
+    }
+
+    pub async fn handle_event(&mut self, event: Event) -> Result<bool, Error> {
+        if let Event::Key(key) = event {
+            match (key.modifiers, key.code) {
+                (_, KeyCode::Enter) => {
+                    if let FestivalField::None = self.current_field {
+                        self.change_focus(self.current_field.next());
+                        return Ok(false);
+                    }
+                }
+
+                (KeyModifiers::CONTROL, KeyCode::Char('j')) => {
+                    self.change_focus(self.current_field.next());
+                    return Ok(false);
+                }
+                (KeyModifiers::CONTROL, KeyCode::Char('k')) => {
+                    self.change_focus(self.current_field.prev());
+                    return Ok(false);
+                }
+                _ => {}
+            }
+        }
+
+        match self.current_field {
+            FestivalField::Name => {
+                if let Some(input_event) = self.name.handle_event(&event) {
+                    match input_event {
+                        TextInputEvent::Escape => self.name.unfocus(),
+                        TextInputEvent::Save => self.change_focus(self.current_field.next()),
+                    }
+                }
+            }
+            FestivalField::Site => {
+                if let Some(list_event) = self.site.handle_event(&event) {
+                    match list_event {
+                        ListInputEvent::Escape => self.site.unfocus(),
+                        ListInputEvent::Select => self.change_focus(self.current_field.next()),
+                    }
+                }
+            }
+            FestivalField::Start => {
+                if let Some(date_event) = self.start.handle_event(&event) {
+                    match date_event {
+                        DateInputEvent::Escape => self.start.unfocus(),
+                        DateInputEvent::Select => {
+                            // Most festivals are over in a few days, so start the end date nearby
+                            if let (Some(start), None) =
+                                (self.start.get_value(), self.end.get_value())
+                            {
+                                self.end.set_cursor(start);
+                            }
+
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+            FestivalField::End => {
+                if let Some(date_event) = self.end.handle_event(&event) {
+                    match date_event {
+                        DateInputEvent::Escape => self.end.unfocus(),
+                        DateInputEvent::Select => self.change_focus(self.current_field.next()),
+                    }
+                }
+            }
+            FestivalField::Stages => {
+                if let Some(input_event) = self.stages.handle_event(&event) {
+                    match input_event {
+                        TextInputEvent::Escape => self.stages.unfocus(),
+                        TextInputEvent::Save => self.change_focus(self.current_field.next()),
+                    }
+                }
+            }
+            FestivalField::Save => {
+                if let Some(save_event) = self.save.handle_event(&event) {
+                    match save_event {
+                        SaveButtonEvent::Escape => self.save.unfocus(),
+                        SaveButtonEvent::Save => {
+                            if let Some(field_error) = self.save_value().await? {
+                                match field_error {
+                                    FestivalFieldError::Name(err) => self.name.set_err(err),
+                                    FestivalFieldError::Site(err) => self.site.set_err(err),
+                                    FestivalFieldError::Start(err) => self.start.set_err(err),
+                                    FestivalFieldError::End(err) => self.end.set_err(err),
+                                    FestivalFieldError::Save(err) => self.save.set_err(err),
+                                }
+                            } else {
+                                return Ok(true);
+                            }
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [top, middle, stages_area, bottom] = Layout::vertical(vec![
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .areas(area);
+
+        let [site_area, start_area, end_area] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Length(24),
+            Constraint::Length(24),
+        ])
+        .areas(middle);
+
+        self.name.render(frame, top);
+        self.site.render(frame, site_area);
+        self.start.render(frame, start_area);
+        self.end.render(frame, end_area);
+        self.stages.render(frame, stages_area);
+        self.save.render(frame, bottom);
+    }
+
+    // The `Some` value of a return is an error message, because of bad data
+    async fn save_value(&self) -> Result<Option<FestivalFieldError>, Error> {
+        let Some(name) = self.name.get_value() else {
+            return Ok(Some(FestivalFieldError::Name(
+                "Field \"Name\" cannot be empty".into(),
+            )));
+        };
+
+        let Some(site) = self.site.get_value() else {
+            return Ok(Some(FestivalFieldError::Site(
+                "Field \"Site\" cannot be empty".into(),
+            )));
+        };
+
+        let Some(start) = self.start.get_value() else {
+            return Ok(Some(FestivalFieldError::Start(
+                "Field \"Start\" cannot be empty".into(),
+            )));
+        };
+
+        // A one day festival doesn't need an end date
+        let end = self.end.get_value().unwrap_or_else(|| start.clone());
+        if end < start {
+            return Ok(Some(FestivalFieldError::End(
+                "The festival can't end before it starts".into(),
+            )));
+        }
+
+        let stages = parse_stages(&self.stages.get_value().unwrap_or_default());
+        let festival = Festival::new(name, site.venue_id, start, end, stages);
+
+        let result = match &self.editing {
+            Some(original) => Festival::update(original, festival, &self.pool).await,
+            None => Festival::save(festival, &self.pool).await,
+        };
+
+        match result {
+            Ok(_) => Ok(None),
+            Err(err) => Ok(Some(FestivalFieldError::Save(err.to_string()))),
+        }
+    }
+}
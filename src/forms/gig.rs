@@ -1,4 +1,15 @@
-use crate::{artist::Artist, dataset::DataSet, date::Date, error::Error, gig::Gig, venue::Venue};
+use crate::{
+    artist::Artist,
+    dataset::DataSet,
+    error::Error,
+    event::{self, Event as GigEvent},
+    festival::{Festival, Stage},
+    gig::{Gig, GigFieldError},
+    person::Person,
+    price,
+    user::User,
+    venue::Venue,
+};
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
@@ -8,25 +19,33 @@ use sqlx::{Pool, Sqlite};
 
 use super::{
     actinput::{ActInput, ActInputEvent},
+    dateinput::{DateInput, DateInputEvent},
+    lineupinput::{LineupInput, LineupInputEvent},
     listinput::{ListInput, ListInputEvent},
+    multilistinput::{MultiListInput, MultiListInputEvent},
+    ratinginput::{RatingInput, RatingInputEvent},
     savebutton::{SaveButton, SaveButtonEvent},
+    textarea::{TextArea, TextAreaEvent},
     textinput::{TextInput, TextInputEvent},
 };
 
-enum GigFieldError {
-    Artist(String),
-    Venue(String),
-    Date(String),
-    Act(String),
-    Save(String),
-}
-
 enum GigField {
     None,
     Artist,
     Venue,
     Date,
     Act,
+    Lineup,
+    Festival,
+    Stage,
+    People,
+    SeenBy,
+    Price,
+    Fees,
+    Currency,
+    TicketType,
+    Rating,
+    Notes,
     Save,
 }
 
@@ -37,7 +56,18 @@ impl GigField {
             GigField::Artist => GigField::Venue,
             GigField::Venue => GigField::Date,
             GigField::Date => GigField::Act,
-            GigField::Act => GigField::Save,
+            GigField::Act => GigField::Lineup,
+            GigField::Lineup => GigField::Festival,
+            GigField::Festival => GigField::Stage,
+            GigField::Stage => GigField::People,
+            GigField::People => GigField::SeenBy,
+            GigField::SeenBy => GigField::Price,
+            GigField::Price => GigField::Fees,
+            GigField::Fees => GigField::Currency,
+            GigField::Currency => GigField::TicketType,
+            GigField::TicketType => GigField::Rating,
+            GigField::Rating => GigField::Notes,
+            GigField::Notes => GigField::Save,
             GigField::Save => GigField::Save,
         }
     }
@@ -49,7 +79,18 @@ impl GigField {
             GigField::Venue => GigField::Artist,
             GigField::Date => GigField::Venue,
             GigField::Act => GigField::Date,
-            GigField::Save => GigField::Act,
+            GigField::Lineup => GigField::Act,
+            GigField::Festival => GigField::Lineup,
+            GigField::Stage => GigField::Festival,
+            GigField::People => GigField::Stage,
+            GigField::SeenBy => GigField::People,
+            GigField::Price => GigField::SeenBy,
+            GigField::Fees => GigField::Price,
+            GigField::Currency => GigField::Fees,
+            GigField::TicketType => GigField::Currency,
+            GigField::Rating => GigField::TicketType,
+            GigField::Notes => GigField::Rating,
+            GigField::Save => GigField::Notes,
         }
     }
 }
@@ -57,39 +98,138 @@ impl GigField {
 pub struct GigForm<'a> {
     pool: Pool<Sqlite>,
     current_field: GigField,
+    editing: Option<Gig>,
 
     artist_input: ListInput<'a, Artist>,
     venue_input: ListInput<'a, Venue>,
-    date_input: TextInput<'a>,
+    date_input: DateInput<'a>,
     act_input: ActInput,
+    lineup_input: LineupInput<'a>,
+    festival_input: ListInput<'a, Festival>,
+    // Every festival's stages, only the selected festival's are listed
+    stages: Vec<Stage>,
+    stage_input: ListInput<'a, Stage>,
+    people_input: MultiListInput<'a, Person>,
+    users_input: MultiListInput<'a, User>,
+    price_input: TextInput<'a>,
+    fees_input: TextInput<'a>,
+    currency_input: TextInput<'a>,
+    ticket_type_input: TextInput<'a>,
+    rating_input: RatingInput<'a>,
+    notes_input: TextArea<'a>,
     save_button: SaveButton,
 }
 
 impl GigForm<'_> {
-    pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
+    // New gigs are put down as seen by `user`, the active user
+    pub async fn new(pool: Pool<Sqlite>, user: Option<&User>) -> Result<Self, Error> {
         let artist_input = ListInput::new("Artist", &pool).await?;
         let venue_input = ListInput::new("Venue", &pool).await?;
-        let date_input = TextInput::new("Date");
+        let date_input = DateInput::new("Date", &pool).await?;
         let act_input = ActInput::new();
+        let lineup_input = LineupInput::new("Lineup");
+        let festival_input = ListInput::new("Festival", &pool).await?;
+        let mut stages = Stage::load_all(&pool).await?;
+        stages.sort_unstable();
+        let stage_input = ListInput::with_values("Stage", Vec::new());
+        let people_input = MultiListInput::new("With", &pool).await?;
+        let mut users_input: MultiListInput<User> = MultiListInput::new("Seen By", &pool).await?;
+        if let Some(user) = user {
+            users_input.select_where(|other| other.user_id == user.user_id);
+        }
+        let price_input = TextInput::new("Price");
+        let fees_input = TextInput::new("Fees");
+        let mut currency_input = TextInput::new("Currency");
+        let ticket_type_input = TextInput::new("Ticket Type");
+        let rating_input = RatingInput::new("Rating");
+        let notes_input = TextArea::new("Notes");
+
+        // Most tickets are bought in the same currency as the last one
+        if let Some(currency) = event::last_currency(&pool).await? {
+            currency_input.set_value(currency);
+        }
         let save_button = SaveButton::new();
 
         Ok(Self {
             pool,
             current_field: GigField::None,
+            editing: None,
             artist_input,
             venue_input,
             date_input,
             act_input,
+            lineup_input,
+            festival_input,
+            stages,
+            stage_input,
+            people_input,
+            users_input,
+            price_input,
+            fees_input,
+            currency_input,
+            ticket_type_input,
+            rating_input,
+            notes_input,
             save_button,
         })
     }
 
+    pub async fn edit(pool: Pool<Sqlite>, gig: Gig) -> Result<Self, Error> {
+        let mut form = Self::new(pool, None).await?;
+
+        form.artist_input
+            .select_where(|artist| artist.artist_id == gig.artist_id);
+        form.venue_input
+            .select_where(|venue| venue.venue_id == gig.venue_id);
+        form.date_input.set_value(gig.date.clone());
+        form.act_input.set_value(gig.act);
+        if let Some(festival_id) = gig.festival_id {
+            form.festival_input
+                .select_where(|festival| festival.festival_id == festival_id);
+        }
+        form.update_stages();
+        if let Some(stage_id) = gig.stage_id {
+            form.stage_input
+                .select_where(|stage| stage.stage_id == stage_id);
+        }
+        form.people_input
+            .select_where(|person| gig.people.contains(&person.name));
+        form.users_input.select_where(|user| user.attended(&gig));
+        form.price_input
+            .set_value(gig.price.map(price::format).unwrap_or_default());
+        form.fees_input
+            .set_value(gig.fees.map(price::format).unwrap_or_default());
+        // Otherwise the last currency used is kept for when a price is added
+        if let Some(currency) = gig.currency.clone() {
+            form.currency_input.set_value(currency);
+        }
+        form.ticket_type_input
+            .set_value(gig.ticket_type.clone().unwrap_or_default());
+        form.rating_input.set_value(gig.rating);
+        form.notes_input
+            .set_value(gig.notes.clone().unwrap_or_default());
+        form.editing = Some(gig);
+
+        Ok(form)
+    }
+
     fn change_focus(&mut self, new_focus: GigField) {
         match self.current_field {
             GigField::Artist => self.artist_input.unfocus(),
             GigField::Venue => self.venue_input.unfocus(),
             GigField::Date => self.date_input.unfocus(),
             GigField::Act => self.act_input.unfocus(),
+            GigField::Lineup => self.lineup_input.unfocus(),
+            GigField::Festival => self.festival_input.unfocus(),
+            GigField::Stage => self.stage_input.unfocus(),
+            GigField::People => self.people_input.unfocus(),
+            GigField::SeenBy => self.users_input.unfocus(),
+            GigField::Price => self.price_input.unfocus(),
+            GigField::Fees => self.fees_input.unfocus(),
+            GigField::Currency => self.currency_input.unfocus(),
+            GigField::TicketType => self.ticket_type_input.unfocus(),
+            GigField::Rating => self.rating_input.unfocus(),
+            GigField::Notes => self.notes_input.unfocus(),
             GigField::Save => self.save_button.unfocus(),
             _ => {}
         }
@@ -101,6 +241,17 @@ impl GigForm<'_> {
             GigField::Venue => self.venue_input.focus(),
             GigField::Date => self.date_input.focus(),
             GigField::Act => self.act_input.focus(),
+            GigField::Lineup => self.lineup_input.focus(),
+            GigField::Festival => self.festival_input.focus(),
+            GigField::Stage => self.stage_input.focus(),
+            GigField::People => self.people_input.focus(),
+            GigField::SeenBy => self.users_input.focus(),
+            GigField::Price => self.price_input.focus(),
+            GigField::Fees => self.fees_input.focus(),
+            GigField::Currency => self.currency_input.focus(),
+            GigField::TicketType => self.ticket_type_input.focus(),
+            GigField::Rating => self.rating_input.focus(),
+            GigField::Notes => self.notes_input.focus(),
             GigField::Save => self.save_button.focus(),
             _ => {}
         }
@@ -156,8 +307,8 @@ impl GigForm<'_> {
             GigField::Date => {
                 if let Some(date_input) = self.date_input.handle_event(&event) {
                     match date_input {
-                        TextInputEvent::Escape => self.date_input.unfocus(),
-                        TextInputEvent::Save => {
+                        DateInputEvent::Escape => self.date_input.unfocus(),
+                        DateInputEvent::Select => {
                             self.change_focus(self.current_field.next());
                         }
                     }
@@ -175,6 +326,88 @@ impl GigForm<'_> {
                 }
             }
 
+            GigField::Lineup => {
+                if let Some(lineup_input) = self.lineup_input.handle_event(&event) {
+                    match lineup_input {
+                        LineupInputEvent::Escape => self.lineup_input.unfocus(),
+                        LineupInputEvent::Add => self.add_to_lineup(),
+                    }
+                }
+            }
+
+            GigField::Festival => {
+                let festival_input = self.festival_input.handle_event(&event);
+                // The festival can be picked or cleared without leaving the field
+                self.update_stages();
+
+                if let Some(festival_input) = festival_input {
+                    match festival_input {
+                        ListInputEvent::Escape => self.festival_input.unfocus(),
+                        ListInputEvent::Select => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+
+            GigField::Stage => {
+                if let Some(stage_input) = self.stage_input.handle_event(&event) {
+                    match stage_input {
+                        ListInputEvent::Escape => self.stage_input.unfocus(),
+                        ListInputEvent::Select => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+
+            GigField::People => {
+                if let Some(MultiListInputEvent::Escape) = self.people_input.handle_event(&event) {
+                    self.people_input.unfocus();
+                }
+            }
+
+            GigField::SeenBy => {
+                if let Some(MultiListInputEvent::Escape) = self.users_input.handle_event(&event) {
+                    self.users_input.unfocus();
+                }
+            }
+
+            GigField::Price | GigField::Fees | GigField::Currency | GigField::TicketType => {
+                let input = match self.current_field {
+                    GigField::Price => &mut self.price_input,
+                    GigField::Fees => &mut self.fees_input,
+                    GigField::Currency => &mut self.currency_input,
+                    _ => &mut self.ticket_type_input,
+                };
+
+                if let Some(text_input) = input.handle_event(&event) {
+                    match text_input {
+                        TextInputEvent::Escape => input.unfocus(),
+                        TextInputEvent::Save => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+
+            GigField::Rating => {
+                if let Some(rating_input) = self.rating_input.handle_event(&event) {
+                    match rating_input {
+                        RatingInputEvent::Escape => self.rating_input.unfocus(),
+                        RatingInputEvent::Select => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+
+            GigField::Notes => {
+                if let Some(TextAreaEvent::Escape) = self.notes_input.handle_event(&event) {
+                    self.notes_input.unfocus();
+                }
+            }
+
             GigField::Save => {
                 if let Some(save_input) = self.save_button.handle_event(&event) {
                     match save_input {
@@ -186,6 +419,16 @@ impl GigForm<'_> {
                                     GigFieldError::Venue(err) => self.venue_input.set_err(err),
                                     GigFieldError::Date(err) => self.date_input.set_err(err),
                                     GigFieldError::Act(err) => self.act_input.set_err(err),
+                                    GigFieldError::Festival(err) => {
+                                        self.festival_input.set_err(err)
+                                    }
+                                    GigFieldError::Stage(err) => self.stage_input.set_err(err),
+                                    GigFieldError::Price(err) => self.price_input.set_err(err),
+                                    GigFieldError::Fees(err) => self.fees_input.set_err(err),
+                                    GigFieldError::Currency(err) => {
+                                        self.currency_input.set_err(err)
+                                    }
+                                    GigFieldError::Rating(err) => self.rating_input.set_err(err),
                                     GigFieldError::Save(err) => self.save_button.set_err(err),
                                 }
                             } else {
@@ -203,70 +446,149 @@ impl GigForm<'_> {
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        let [top, middle, bottom] = Layout::vertical(vec![
+        let [top, festival_row, ticket_row, review_row, middle, bottom] = Layout::vertical(vec![
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Length(3),
             Constraint::Fill(1),
             Constraint::Length(3),
             Constraint::Length(3),
         ])
         .areas(area);
 
-        let [top_left, top_right] = Layout::horizontal([Constraint::Fill(1); 2]).areas(top);
-        let [mid_left, mid_right] =
-            Layout::horizontal(vec![Constraint::Length(12), Constraint::Fill(1)]).areas(middle);
+        let [artist_area, venue_area, date_area, lineup_area] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(24),
+            Constraint::Fill(1),
+        ])
+        .areas(top);
+
+        self.artist_input.render(frame, artist_area);
+        self.venue_input.render(frame, venue_area);
+        self.date_input.render(frame, date_area);
+        self.lineup_input.render(frame, lineup_area);
+
+        let [festival_area, stage_area, people_area, users_area] =
+            Layout::horizontal([Constraint::Fill(1); 4]).areas(festival_row);
 
-        self.artist_input.render(frame, top_left);
-        self.venue_input.render(frame, top_right);
-        self.date_input.render(frame, mid_left);
-        self.act_input.render(frame, mid_right);
+        self.festival_input.render(frame, festival_area);
+        self.stage_input.render(frame, stage_area);
+        self.people_input.render(frame, people_area);
+        self.users_input.render(frame, users_area);
+
+        let [price_area, fees_area, currency_area, ticket_type_area] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Fill(2),
+        ])
+        .areas(ticket_row);
+
+        self.price_input.render(frame, price_area);
+        self.fees_input.render(frame, fees_area);
+        self.currency_input.render(frame, currency_area);
+        self.ticket_type_input.render(frame, ticket_type_area);
+
+        let [rating_area, notes_area] =
+            Layout::horizontal([Constraint::Length(24), Constraint::Fill(1)]).areas(review_row);
+
+        self.rating_input.render(frame, rating_area);
+        self.notes_input.render(frame, notes_area);
+        self.act_input.render(frame, middle);
         self.save_button.render(frame, bottom);
     }
 
-    async fn save_value(&self) -> Result<Option<GigFieldError>, Error> {
-        let artist_id = match self.artist_input.get_value() {
-            Some(artist) => artist.artist_id,
-            None => {
-                return Ok(Some(GigFieldError::Artist(
-                    "Field \"Artist\" cannot be empty".into(),
-                )))
-            }
-        };
+    // Only the selected festival's stages can be picked
+    fn update_stages(&mut self) {
+        let festival_id = self
+            .festival_input
+            .get_value()
+            .map(|festival| festival.festival_id);
+
+        self.stage_input.set_values(
+            self.stages
+                .iter()
+                .filter(|stage| Some(stage.festival_id) == festival_id)
+                .cloned()
+                .collect(),
+        );
+    }
 
-        let venue_id = match self.venue_input.get_value() {
-            Some(venue) => venue.venue_id,
-            None => {
-                return Ok(Some(GigFieldError::Venue(
-                    "Field \"Venue\" cannot be empty".into(),
-                )))
+    // Adds the selected artist and act to the lineup, then goes back to pick the next artist
+    fn add_to_lineup(&mut self) {
+        if self.editing.is_some() {
+            self.lineup_input
+                .set_err("The lineup can't be changed while editing a gig".into());
+            return;
+        }
+
+        match (self.artist_input.get_value(), self.act_input.get_value()) {
+            (Some(artist), Some(act)) => {
+                self.lineup_input.push(artist, act);
+                self.change_focus(GigField::Artist);
             }
-        };
+            _ => self
+                .lineup_input
+                .set_err("Pick an artist and act to add".into()),
+        }
+    }
 
-        let date = match self.date_input.get_value() {
-            Some(date) => {
-                if !Date::is_valid(&date) {
-                    return Ok(Some(GigFieldError::Date("Invalid Date Format".into())));
-                }
+    async fn save_value(&self) -> Result<Option<GigFieldError>, Error> {
+        let lineup = self.lineup_input.get_value();
 
-                Date::from(date)
-            }
-            None => {
-                return Ok(Some(GigFieldError::Date(
-                    "Field \"Date\" cannot be empty".into(),
-                )))
-            }
+        // With a lineup the artist and act fields are only used to add to it
+        let (artist, act) = match lineup.first() {
+            Some((artist, act)) => (Some(artist.clone()), Some(*act)),
+            None => (self.artist_input.get_value(), self.act_input.get_value()),
         };
 
-        let act = match self.act_input.get_value() {
-            Some(act) => act,
+        let gig = match Gig::validate(
+            artist,
+            self.venue_input.get_value(),
+            self.date_input.get_value(),
+            act,
+        )
+        .and_then(|gig| {
+            gig.at_festival(
+                self.festival_input.get_value(),
+                self.stage_input.get_value(),
+            )
+        })
+        .and_then(|gig| {
+            gig.with_ticket(
+                self.price_input.get_value(),
+                self.fees_input.get_value(),
+                self.currency_input.get_value(),
+                self.ticket_type_input.get_value(),
+            )
+        })
+        .and_then(|gig| {
+            gig.with_review(self.rating_input.get_value(), self.notes_input.get_value())
+        })
+        .map(|gig| {
+            gig.with_people(self.people_input.get_values())
+                .with_users(self.users_input.get_values())
+        }) {
+            Ok(gig) => gig,
+            Err(field_error) => return Ok(Some(field_error)),
+        };
+
+        let result = match &self.editing {
+            Some(original) => Gig::update(original, gig, &self.pool).await,
+            None if lineup.is_empty() => Gig::save(gig, &self.pool).await,
+            // Everyone on the lineup gets the same rating and notes, they can be changed per gig later
             None => {
-                return Ok(Some(GigFieldError::Act(
-                    "Field \"Act\" cannot be empty".into(),
-                )))
+                let lineup = lineup
+                    .into_iter()
+                    .map(|(artist, act)| (artist.artist_id, act))
+                    .collect();
+
+                GigEvent::save_lineup(&gig, lineup, &self.pool).await
             }
         };
 
-        let gig = Gig::new(artist_id, venue_id, date, act);
-
-        match Gig::save(gig, &self.pool).await {
+        match result {
             Ok(_) => Ok(None),
             Err(err) => Ok(Some(GigFieldError::Save(err.to_string()))),
         }
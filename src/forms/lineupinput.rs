@@ -0,0 +1,125 @@
+use crate::{act::Act, artist::Artist};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, List, ListState},
+    Frame,
+};
+
+pub enum LineupInputEvent {
+    Escape,
+    Add,
+}
+
+pub struct LineupInput<'a> {
+    title: &'a str,
+    focused: bool,
+    error: Option<String>,
+
+    // In running order
+    lineup: Vec<(Artist, Act)>,
+    state: ListState,
+}
+
+impl<'a> LineupInput<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            focused: false,
+            error: None,
+            lineup: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn set_err(&mut self, err: String) {
+        self.error = Some(err);
+    }
+
+    pub fn get_value(&self) -> Vec<(Artist, Act)> {
+        self.lineup.clone()
+    }
+
+    pub fn push(&mut self, artist: Artist, act: Act) {
+        if self
+            .lineup
+            .iter()
+            .any(|(added, _)| added.artist_id == artist.artist_id)
+        {
+            self.error = Some(format!("{} is already on the lineup", artist));
+            return;
+        }
+
+        self.error = None;
+        self.lineup.push((artist, act));
+        self.state.select_last();
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<LineupInputEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(LineupInputEvent::Escape),
+                KeyCode::Enter => return Some(LineupInputEvent::Add),
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::Char('J') => self.move_selected(1),
+                KeyCode::Char('K') => self.move_selected(-1),
+                KeyCode::Char('d') | KeyCode::Backspace => {
+                    if let Some(idx) = self.state.selected().filter(|&idx| idx < self.lineup.len())
+                    {
+                        self.lineup.remove(idx);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title_top(self.title);
+
+        if self.focused {
+            block = block
+                .border_type(BorderType::Double)
+                .title_bottom("Enter: Add d: Remove J/K: Move");
+        }
+
+        if let Some(err) = self.error.clone() {
+            block = block.border_style(Style::new().red()).title_bottom(err);
+        }
+
+        let items: Vec<String> = self
+            .lineup
+            .iter()
+            .enumerate()
+            .map(|(idx, (artist, act))| format!("{}. {} ({})", idx + 1, artist, act))
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::new().on_gray())
+            .block(block);
+
+        frame.render_stateful_widget(list, area, &mut self.state);
+    }
+
+    fn move_selected(&mut self, by: isize) {
+        let Some(idx) = self.state.selected().filter(|&idx| idx < self.lineup.len()) else {
+            return;
+        };
+
+        let target = idx.saturating_add_signed(by).min(self.lineup.len() - 1);
+        self.lineup.swap(idx, target);
+        self.state.select(Some(target));
+    }
+}
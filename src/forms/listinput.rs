@@ -1,4 +1,10 @@
-use crate::{dataset::DataSet, error::Error};
+use std::cmp::Reverse;
+
+use crate::{
+    dataset::DataSet,
+    error::Error,
+    fuzzy::{fuzzy_match, highlight, FuzzyMatch},
+};
 use crossterm::event::{Event, KeyCode};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
@@ -29,15 +35,21 @@ pub struct ListInput<'a, T> {
     state: ListState,
 }
 
-impl<'a, T: DataSet + Into<ListItem<'a>> + Ord> ListInput<'a, T> {
+impl<'a, T: DataSet + Ord> ListInput<'a, T> {
     pub async fn new(title: &'a str, pool: &Pool<Sqlite>) -> Result<Self, Error> {
         let mut values = T::load_all(pool).await?;
         values.sort_unstable_by(|l, r| l.key().cmp(&r.key()));
-        let filtered_values = values.clone();
 
-        let list = List::new(values.clone()).highlight_style(Style::new().on_gray());
+        Ok(Self::with_values(title, values))
+    }
+}
+
+impl<'a, T: Clone + ToString> ListInput<'a, T> {
+    // For values which aren't a `DataSet` of their own, listed in the order given
+    pub fn with_values(title: &'a str, values: Vec<T>) -> Self {
+        let list = List::default().highlight_style(Style::new().on_gray());
 
-        Ok(Self {
+        let mut list_input = Self {
             title,
             error: None,
             searching: false,
@@ -46,10 +58,14 @@ impl<'a, T: DataSet + Into<ListItem<'a>> + Ord> ListInput<'a, T> {
             selected: None,
             selected_idx: 0,
             values,
-            filtered_values,
+            filtered_values: Vec::new(),
             list,
             state: ListState::default(),
-        })
+        };
+
+        list_input.update_filter();
+
+        list_input
     }
 
     pub fn handle_event(&mut self, event: &Event) -> Option<ListInputEvent> {
@@ -123,20 +139,19 @@ impl<'a, T: DataSet + Into<ListItem<'a>> + Ord> ListInput<'a, T> {
                     }
                 }
 
-                KeyCode::Char(char) => {
-                    if self.searching {
-                        self.search_text.push(char);
-                        self.update_filter();
-                    }
+                KeyCode::Char(char) if self.searching => {
+                    self.search_text.push(char);
+                    self.update_filter();
                 }
 
-                KeyCode::Backspace => {
-                    if self.searching {
-                        self.search_text.pop();
-                        self.update_filter();
-                    }
+                KeyCode::Backspace if self.searching => {
+                    self.search_text.pop();
+                    self.update_filter();
                 }
 
+                // Lets an optional field be left empty again
+                KeyCode::Backspace => self.selected = None,
+
                 _ => {}
             }
         }
@@ -175,6 +190,38 @@ impl<'a, T: DataSet + Into<ListItem<'a>> + Ord> ListInput<'a, T> {
         }
     }
 
+    pub fn select_where(&mut self, predicate: impl Fn(&T) -> bool) {
+        if let Some(idx) = self.filtered_values.iter().position(predicate) {
+            self.selected = Some(self.filtered_values[idx].clone());
+            self.selected_idx = idx;
+            self.state.select(Some(idx));
+        }
+    }
+
+    // Swaps the values to pick from, the selection is kept if it's still one of them
+    pub fn set_values(&mut self, values: Vec<T>)
+    where
+        T: PartialEq,
+    {
+        let selected = self.selected.take();
+        self.values = values;
+        self.selected_idx = 0;
+        self.state.select(None);
+        self.update_filter();
+
+        if let Some(selected) = selected {
+            self.select_where(|value| *value == selected);
+        }
+    }
+
+    pub fn find_all(&self, predicate: impl Fn(&T) -> bool) -> Vec<T> {
+        self.values
+            .iter()
+            .filter(|value| predicate(value))
+            .cloned()
+            .collect()
+    }
+
     pub fn get_value(&self) -> Option<T> {
         self.selected.clone()
     }
@@ -198,13 +245,26 @@ impl<'a, T: DataSet + Into<ListItem<'a>> + Ord> ListInput<'a, T> {
     }
 
     fn update_filter(&mut self) {
-        self.filtered_values = self
+        let mut matches: Vec<(T, String, FuzzyMatch)> = self
             .values
             .iter()
-            .filter(|row| row.contains(self.search_text.clone()))
-            .cloned()
+            .filter_map(|value| {
+                let name = value.to_string();
+                let found = fuzzy_match(&self.search_text, std::slice::from_ref(&name))?;
+
+                Some((value.clone(), name, found))
+            })
+            .collect();
+
+        // Best matches first, equal scores stay in alphabetical order
+        matches.sort_by_key(|(.., found)| Reverse(found.score));
+
+        let items: Vec<ListItem> = matches
+            .iter()
+            .map(|(_, name, found)| ListItem::new(highlight(name, &found.indices[0])))
             .collect();
 
-        self.list = self.list.clone().items(self.filtered_values.clone());
+        self.filtered_values = matches.into_iter().map(|(value, ..)| value).collect();
+        self.list = self.list.clone().items(items);
     }
 }
@@ -1,8 +1,10 @@
-use crate::error::Error;
+use crate::{error::Error, record::Record, user::User};
 use artist::ArtistForm;
 use city::CityForm;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
+use festival::FestivalForm;
 use gig::GigForm;
+use person::PersonForm;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::Stylize,
@@ -16,19 +18,32 @@ mod actinput;
 mod artist;
 mod avfield;
 mod city;
+mod dateinput;
+pub mod delete;
+mod festival;
 mod gig;
+mod lineupinput;
 mod listinput;
+mod multilistinput;
+mod person;
+mod ratinginput;
 mod savebutton;
+pub mod setlist;
+mod taginput;
+mod textarea;
 mod textinput;
+pub mod user;
 mod venue;
 
-const FORM_TABS: [&str; 4] = ["Artist", "Venue", "Gig", "City"];
+const FORM_TABS: [&str; 6] = ["Artist", "Venue", "Gig", "City", "Festival", "Person"];
 
 enum FormTabs {
     Artist = 0,
     Venue,
     Gig,
     City,
+    Festival,
+    Person,
 }
 
 impl FormTabs {
@@ -37,7 +52,9 @@ impl FormTabs {
             FormTabs::Artist => FormTabs::Venue,
             FormTabs::Venue => FormTabs::Gig,
             FormTabs::Gig => FormTabs::City,
-            FormTabs::City => FormTabs::City,
+            FormTabs::City => FormTabs::Festival,
+            FormTabs::Festival => FormTabs::Person,
+            FormTabs::Person => FormTabs::Person,
         }
     }
 
@@ -47,6 +64,8 @@ impl FormTabs {
             FormTabs::Venue => FormTabs::Artist,
             FormTabs::Gig => FormTabs::Venue,
             FormTabs::City => FormTabs::Gig,
+            FormTabs::Festival => FormTabs::City,
+            FormTabs::Person => FormTabs::Festival,
         }
     }
 }
@@ -58,46 +77,103 @@ impl From<&FormTabs> for Option<usize> {
             FormTabs::Venue => 1,
             FormTabs::Gig => 2,
             FormTabs::City => 3,
+            FormTabs::Festival => 4,
+            FormTabs::Person => 5,
         })
     }
 }
 
 pub struct Form<'a> {
     pool: Pool<Sqlite>,
+    // Whose gigs new ones are put down as
+    user: Option<User>,
     current_tab: FormTabs,
     tabs: Tabs<'a>,
+    editing: bool,
 
     artist_form: ArtistForm<'a>,
     venue_form: VenueForm<'a>,
     gig_form: GigForm<'a>,
     city_form: CityForm<'a>,
+    festival_form: FestivalForm<'a>,
+    person_form: PersonForm<'a>,
 }
 
 impl Form<'_> {
-    pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
+    pub async fn new(pool: Pool<Sqlite>, user: Option<User>) -> Result<Self, Error> {
         let tabs = Tabs::new(FORM_TABS);
 
         let artist_form = ArtistForm::new(pool.clone()).await?;
         let venue_form = VenueForm::new(pool.clone()).await?;
-        let gig_form = GigForm::new(pool.clone()).await?;
+        let gig_form = GigForm::new(pool.clone(), user.as_ref()).await?;
         let city_form = CityForm::new(pool.clone());
+        let festival_form = FestivalForm::new(pool.clone()).await?;
+        let person_form = PersonForm::new(pool.clone());
 
         Ok(Self {
             pool,
+            user,
             tabs,
             current_tab: FormTabs::Artist,
+            editing: false,
             artist_form,
             venue_form,
             gig_form,
             city_form,
+            festival_form,
+            person_form,
         })
     }
 
+    // Takes effect on the next `reset_and_reload`
+    pub fn set_user(&mut self, user: Option<User>) {
+        self.user = user;
+    }
+
     pub async fn reset_and_reload(&mut self) -> Result<(), Error> {
         self.artist_form = ArtistForm::new(self.pool.clone()).await?;
         self.venue_form = VenueForm::new(self.pool.clone()).await?;
-        self.gig_form = GigForm::new(self.pool.clone()).await?;
+        self.gig_form = GigForm::new(self.pool.clone(), self.user.as_ref()).await?;
         self.city_form = CityForm::new(self.pool.clone());
+        self.festival_form = FestivalForm::new(self.pool.clone()).await?;
+        self.person_form = PersonForm::new(self.pool.clone());
+        self.editing = false;
+
+        Ok(())
+    }
+
+    pub async fn edit(&mut self, record: Record) -> Result<(), Error> {
+        self.current_tab = match record {
+            Record::Artist(artist) => {
+                self.artist_form = ArtistForm::edit(self.pool.clone(), artist).await?;
+                FormTabs::Artist
+            }
+            Record::Venue(venue) => {
+                self.venue_form = VenueForm::edit(self.pool.clone(), venue).await?;
+                FormTabs::Venue
+            }
+            Record::Gig(gig) => {
+                self.gig_form = GigForm::edit(self.pool.clone(), *gig).await?;
+                FormTabs::Gig
+            }
+            Record::City(city) => {
+                self.city_form = CityForm::edit(self.pool.clone(), city);
+                FormTabs::City
+            }
+            Record::Festival(festival) => {
+                self.festival_form = FestivalForm::edit(self.pool.clone(), festival).await?;
+                FormTabs::Festival
+            }
+            Record::Person(person) => {
+                self.person_form = PersonForm::edit(self.pool.clone(), person);
+                FormTabs::Person
+            }
+            // Users are only added and deleted, from the user selector
+            Record::User(_) => return Ok(()),
+        };
+
+        self.tabs = self.tabs.clone().select(&self.current_tab);
+        self.editing = true;
 
         Ok(())
     }
@@ -105,6 +181,8 @@ impl Form<'_> {
     pub async fn handle_event(&mut self, event: Event) -> Result<bool, Error> {
         if let Event::Key(key) = event {
             match (key.modifiers, key.code) {
+                // An edit is bound to the record it was opened for
+                (KeyModifiers::CONTROL, KeyCode::Char('l' | 'h')) if self.editing => {}
                 (KeyModifiers::CONTROL, KeyCode::Char('l')) => {
                     self.current_tab = self.current_tab.next();
                     self.tabs = self.tabs.clone().select(&self.current_tab);
@@ -122,24 +200,35 @@ impl Form<'_> {
             FormTabs::Venue => self.venue_form.handle_event(event).await?,
             FormTabs::Gig => self.gig_form.handle_event(event).await?,
             FormTabs::City => self.city_form.handle_event(event).await?,
+            FormTabs::Festival => self.festival_form.handle_event(event).await?,
+            FormTabs::Person => self.person_form.handle_event(event).await?,
         })
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // The gig form has far more fields than the rest, so it gets most of the screen
+        let size = match self.current_tab {
+            FormTabs::Gig => 90,
+            _ => 50,
+        };
+
         let [_, mid_col, _] = Layout::horizontal(vec![
             Constraint::Fill(1),
-            Constraint::Percentage(50),
+            Constraint::Percentage(size),
             Constraint::Fill(1),
         ])
         .areas(area);
         let [_, mid_area, _] = Layout::vertical(vec![
             Constraint::Fill(1),
-            Constraint::Percentage(50),
+            Constraint::Percentage(size),
             Constraint::Fill(1),
         ])
         .areas(mid_col);
 
-        let block = Block::bordered().white().title("Create New");
+        let block =
+            Block::bordered()
+                .white()
+                .title(if self.editing { "Edit" } else { "Create New" });
 
         let [tabs_area, content_area] =
             Layout::vertical([Constraint::Length(2), Constraint::Fill(1)])
@@ -161,6 +250,8 @@ impl Form<'_> {
             FormTabs::Venue => self.venue_form.render(frame, content_area),
             FormTabs::Gig => self.gig_form.render(frame, content_area),
             FormTabs::City => self.city_form.render(frame, content_area),
+            FormTabs::Festival => self.festival_form.render(frame, content_area),
+            FormTabs::Person => self.person_form.render(frame, content_area),
         }
     }
 }
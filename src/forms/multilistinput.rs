@@ -0,0 +1,108 @@
+use crate::{dataset::DataSet, error::Error};
+use crossterm::event::Event;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use super::listinput::{ListInput, ListInputEvent};
+
+pub enum MultiListInputEvent {
+    Escape,
+}
+
+// A `ListInput` where `Enter` ticks values on and off, so any number of them can be picked
+pub struct MultiListInput<'a, T> {
+    title: &'a str,
+    inner: ListInput<'a, T>,
+    chosen: Vec<T>,
+    focused: bool,
+}
+
+impl<'a, T: DataSet + Ord> MultiListInput<'a, T> {
+    pub async fn new(title: &'a str, pool: &Pool<Sqlite>) -> Result<Self, Error> {
+        Ok(Self {
+            title,
+            inner: ListInput::new(title, pool).await?,
+            chosen: Vec::new(),
+            focused: false,
+        })
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<MultiListInputEvent> {
+        match self.inner.handle_event(event)? {
+            ListInputEvent::Escape => Some(MultiListInputEvent::Escape),
+            ListInputEvent::Select => {
+                if let Some(value) = self.inner.get_value() {
+                    self.toggle(value);
+                }
+
+                None
+            }
+        }
+    }
+
+    fn toggle(&mut self, value: T) {
+        match self.chosen.iter().position(|chosen| chosen == &value) {
+            Some(idx) => {
+                self.chosen.remove(idx);
+            }
+            None => {
+                self.chosen.push(value);
+                self.chosen.sort_unstable();
+            }
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let names = self
+            .chosen
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if self.focused {
+            let [list_area, chosen_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+            self.inner.render(frame, list_area);
+            frame.render_widget(
+                Line::from(format!("Picked: {}", names))
+                    .dark_gray()
+                    .italic(),
+                chosen_area,
+            );
+        } else {
+            frame.render_widget(
+                Paragraph::new(names)
+                    .wrap(Wrap { trim: true })
+                    .block(Block::bordered().title_top(self.title)),
+                area,
+            );
+        }
+    }
+
+    pub fn select_where(&mut self, predicate: impl Fn(&T) -> bool) {
+        self.chosen = self.inner.find_all(predicate);
+        self.chosen.sort_unstable();
+    }
+
+    pub fn get_values(&self) -> Vec<T> {
+        self.chosen.clone()
+    }
+
+    pub fn focus(&mut self) {
+        self.inner.focus();
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.inner.unfocus();
+        self.focused = false;
+    }
+}
@@ -0,0 +1,175 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{dataset::DataSet, error::Error, person::Person};
+
+use super::{
+    savebutton::{SaveButton, SaveButtonEvent},
+    textinput::{TextInput, TextInputEvent},
+};
+
+enum PersonFieldError {
+    Name(String),
+    Save(String),
+}
+
+enum PersonField {
+    None,
+    Name,
+    Save,
+}
+
+impl PersonField {
+    fn next(&self) -> Self {
+        match self {
+            PersonField::None => PersonField::Name,
+            PersonField::Name => PersonField::Save,
+            PersonField::Save => PersonField::Save,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        match self {
+            PersonField::None => PersonField::None,
+            PersonField::Name => PersonField::None,
+            PersonField::Save => PersonField::Name,
+        }
+    }
+}
+
+pub struct PersonForm<'a> {
+    pool: Pool<Sqlite>,
+    current_field: PersonField,
+    editing: Option<Person>,
+    name: TextInput<'a>,
+    save: SaveButton,
+}
+
+impl PersonForm<'_> {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            current_field: PersonField::None,
+            editing: None,
+            name: TextInput::new("Name"),
+            save: SaveButton::new(),
+        }
+    }
+
+    pub fn edit(pool: Pool<Sqlite>, person: Person) -> Self {
+        let mut form = Self::new(pool);
+
+        form.name.set_value(person.name.clone());
+        form.editing = Some(person);
+
+        form
+    }
+
+    fn change_focus(&mut self, new_focus: PersonField) {
+        match self.current_field {
+            PersonField::Name => self.name.unfocus(),
+            PersonField::Save => self.save.unfocus(),
+            _ => {}
+        }
+
+        self.current_field = new_focus;
+
+        match self.current_field {
+            PersonField::Name => self.name.focus(),
+            PersonField::Save => self.save.focus(),
+            _ => {}
+        }
+    }
+
+    pub async fn handle_event(&mut self, event: Event) -> Result<bool, Error> {
+        if let Event::Key(key) = event {
+            match (key.modifiers, key.code) {
+                (_, KeyCode::Enter) => {
+                    if let PersonField::None = self.current_field {
+                        self.change_focus(self.current_field.next());
+                        return Ok(false);
+                    }
+                }
+                (KeyModifiers::CONTROL, KeyCode::Char('j')) => {
+                    self.change_focus(self.current_field.next());
+                    return Ok(false);
+                }
+                (KeyModifiers::CONTROL, KeyCode::Char('k')) => {
+                    self.change_focus(self.current_field.prev());
+                    return Ok(false);
+                }
+                _ => {}
+            }
+        }
+
+        match self.current_field {
+            PersonField::Name => {
+                if let Some(input_event) = self.name.handle_event(&event) {
+                    match input_event {
+                        TextInputEvent::Escape => {
+                            self.name.unfocus();
+                        }
+                        TextInputEvent::Save => {
+                            self.change_focus(self.current_field.next());
+                        }
+                    }
+                }
+            }
+            PersonField::Save => {
+                if let Some(save_event) = self.save.handle_event(&event) {
+                    match save_event {
+                        SaveButtonEvent::Escape => self.save.unfocus(),
+                        SaveButtonEvent::Save => {
+                            if let Some(field_error) = self.save_value().await? {
+                                match field_error {
+                                    PersonFieldError::Name(error) => self.name.set_err(error),
+                                    PersonFieldError::Save(error) => self.save.set_err(error),
+                                }
+                            } else {
+                                return Ok(true);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [top, bottom] =
+            Layout::vertical(vec![Constraint::Length(3), Constraint::Length(3)]).areas(area);
+
+        self.name.render(frame, top);
+        self.save.render(frame, bottom);
+    }
+
+    async fn save_value(&self) -> Result<Option<PersonFieldError>, Error> {
+        let name = match self.name.get_value() {
+            Some(name) => name,
+            None => {
+                return Ok(Some(PersonFieldError::Name(
+                    "Field \"Name\" cannot be empty".into(),
+                )));
+            }
+        };
+
+        let person = Person::new(name);
+
+        let result = match &self.editing {
+            Some(original) => Person::update(original, person, &self.pool).await,
+            None => Person::save(person, &self.pool).await,
+        };
+
+        match result {
+            Ok(_) => Ok(None),
+            Err(err) => Ok(Some(PersonFieldError::Save(err.to_string()))),
+        }
+    }
+}
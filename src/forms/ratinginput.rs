@@ -0,0 +1,100 @@
+use crate::rating;
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType},
+    Frame,
+};
+
+pub enum RatingInputEvent {
+    Escape,
+    Select,
+}
+
+pub struct RatingInput<'a> {
+    title: &'a str,
+    focused: bool,
+    value: Option<i64>,
+    error: Option<String>,
+}
+
+impl<'a> RatingInput<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            focused: false,
+            value: None,
+            error: None,
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn set_value(&mut self, rating: Option<i64>) {
+        self.value = rating;
+    }
+
+    pub fn get_value(&self) -> Option<i64> {
+        self.value
+    }
+
+    pub fn set_err(&mut self, err: String) {
+        self.error = Some(err);
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<RatingInputEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(RatingInputEvent::Escape),
+                KeyCode::Enter => return Some(RatingInputEvent::Select),
+                KeyCode::Char('l') => {
+                    self.value = Some(self.value.map_or(1, |value| (value + 1).min(rating::MAX)));
+                }
+                // Going below one star leaves the gig unrated
+                KeyCode::Char('h') => {
+                    self.value = self.value.filter(|value| *value > 1).map(|value| value - 1);
+                }
+                KeyCode::Char(char) => {
+                    if let Some(digit) = char.to_digit(10) {
+                        self.value = rating::validate(i64::from(digit)).ok();
+                    }
+                }
+                KeyCode::Backspace => self.value = None,
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title_top(self.title);
+        let content_area = block.inner(area);
+
+        if self.focused {
+            block = block
+                .border_type(BorderType::Double)
+                .title_bottom("h/l or 1-5");
+        }
+
+        if let Some(err) = self.error.clone() {
+            block = block.border_style(Style::new().red()).title_bottom(err);
+        }
+
+        let line = match self.value {
+            Some(value) => Line::from(rating::stars(value)).yellow(),
+            None => Line::from("Not rated").dark_gray(),
+        };
+
+        frame.render_widget(block, area);
+        frame.render_widget(line, content_area);
+    }
+}
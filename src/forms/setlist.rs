@@ -0,0 +1,345 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Row, Table, TableState},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    error::Error,
+    gig::Gig,
+    setlist::{Setlist, Song},
+};
+
+use super::textinput::{TextInput, TextInputEvent};
+
+pub enum SetlistFormEvent {
+    Close,
+    Saved,
+}
+
+enum EntryField {
+    Title,
+    CoverOf,
+    Notes,
+}
+
+// The song being added or changed, `Enter` moves through the fields and adds it after the last
+struct Entry {
+    // `None` when adding a new song
+    editing: Option<usize>,
+    field: EntryField,
+
+    title: TextInput<'static>,
+    cover_of: TextInput<'static>,
+    notes: TextInput<'static>,
+}
+
+impl Entry {
+    fn new(editing: Option<usize>, song: Option<&Song>) -> Self {
+        let mut entry = Self {
+            editing,
+            field: EntryField::Title,
+            title: TextInput::new("Song"),
+            cover_of: TextInput::new("Cover Of"),
+            notes: TextInput::new("Notes"),
+        };
+
+        if let Some(song) = song {
+            entry.title.set_value(song.title.clone());
+            entry
+                .cover_of
+                .set_value(song.cover_of.clone().unwrap_or_default());
+            entry
+                .notes
+                .set_value(song.notes.clone().unwrap_or_default());
+        }
+
+        entry.title.focus();
+        entry
+    }
+
+    fn input(&mut self) -> &mut TextInput<'static> {
+        match self.field {
+            EntryField::Title => &mut self.title,
+            EntryField::CoverOf => &mut self.cover_of,
+            EntryField::Notes => &mut self.notes,
+        }
+    }
+
+    fn next(&mut self) {
+        self.input().unfocus();
+
+        self.field = match self.field {
+            EntryField::Title => EntryField::CoverOf,
+            EntryField::CoverOf | EntryField::Notes => EntryField::Notes,
+        };
+
+        self.input().focus();
+    }
+}
+
+// A popup for writing down the songs an artist played at a gig. Nothing is saved until `s`
+pub struct SetlistForm {
+    pool: Pool<Sqlite>,
+    gig: Gig,
+    setlist: Setlist,
+    error: Option<String>,
+
+    entry: Option<Entry>,
+    state: TableState,
+}
+
+impl SetlistForm {
+    pub async fn new(pool: Pool<Sqlite>, gig: Gig) -> Result<Self, Error> {
+        let setlist = Setlist::load(&gig, &pool).await?;
+
+        let mut state = TableState::default();
+        state.select_first();
+
+        Ok(Self {
+            pool,
+            gig,
+            setlist,
+            error: None,
+            entry: None,
+            state,
+        })
+    }
+
+    pub async fn handle_event(&mut self, event: &Event) -> Result<Option<SetlistFormEvent>, Error> {
+        if self.entry.is_some() {
+            self.handle_entry_event(event);
+            return Ok(None);
+        }
+
+        let selected = self
+            .state
+            .selected()
+            .filter(|idx| *idx < self.setlist.songs.len());
+
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(SetlistFormEvent::Close)),
+                KeyCode::Char('s') => {
+                    return match self.setlist.save(&self.pool).await {
+                        Ok(_) => Ok(Some(SetlistFormEvent::Saved)),
+                        Err(err) => {
+                            self.error = Some(err.to_string());
+                            Ok(None)
+                        }
+                    };
+                }
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::Char('a') => self.entry = Some(Entry::new(None, None)),
+                KeyCode::Char('e') | KeyCode::Enter => {
+                    if let Some(idx) = selected {
+                        self.entry = Some(Entry::new(Some(idx), self.setlist.songs.get(idx)));
+                    }
+                }
+                KeyCode::Char('d') => {
+                    if let Some(idx) = selected {
+                        self.setlist.songs.remove(idx);
+                    }
+                }
+                KeyCode::Char('x') => {
+                    if let Some(idx) = selected {
+                        self.setlist.songs[idx].encore = !self.setlist.songs[idx].encore;
+                    }
+                }
+                KeyCode::Char('J') => {
+                    if let Some(idx) = selected.filter(|idx| idx + 1 < self.setlist.songs.len()) {
+                        self.setlist.songs.swap(idx, idx + 1);
+                        self.state.select(Some(idx + 1));
+                    }
+                }
+                KeyCode::Char('K') => {
+                    if let Some(idx) = selected.filter(|idx| *idx > 0) {
+                        self.setlist.songs.swap(idx, idx - 1);
+                        self.state.select(Some(idx - 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn handle_entry_event(&mut self, event: &Event) {
+        let Some(entry) = self.entry.as_mut() else {
+            return;
+        };
+
+        match entry.input().handle_event(event) {
+            Some(TextInputEvent::Escape) => self.entry = None,
+            Some(TextInputEvent::Save) => {
+                if let EntryField::Notes = entry.field {
+                    self.add_entry();
+                } else {
+                    entry.next();
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn add_entry(&mut self) {
+        let Some(entry) = self.entry.as_mut() else {
+            return;
+        };
+
+        let Some(title) = entry.title.get_value() else {
+            entry.title.set_err("A song needs a title".into());
+            entry.input().unfocus();
+            entry.field = EntryField::Title;
+            entry.title.focus();
+            return;
+        };
+
+        let song = Song::new(
+            title.trim().to_string(),
+            entry.cover_of.get_value(),
+            entry.notes.get_value(),
+        );
+
+        match entry.editing {
+            Some(idx) => {
+                let encore = self.setlist.songs[idx].encore;
+                self.setlist.songs[idx] = Song { encore, ..song };
+            }
+            // Goes after the selected song, so songs can be slotted in where they were missed
+            None => {
+                let idx = self
+                    .state
+                    .selected()
+                    .map_or(self.setlist.songs.len(), |idx| idx + 1)
+                    .min(self.setlist.songs.len());
+
+                // Anything added during the encore is part of it
+                let encore = idx > 0 && self.setlist.songs[idx - 1].encore;
+                self.setlist.songs.insert(idx, Song { encore, ..song });
+                self.state.select(Some(idx));
+            }
+        }
+
+        self.entry = None;
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, mid_col, _] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(70),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, mid_area, _] = Layout::vertical(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(70),
+            Constraint::Fill(1),
+        ])
+        .areas(mid_col);
+
+        let mut block = Block::bordered()
+            .border_type(BorderType::Double)
+            .white()
+            .title(format!(
+                "Setlist: {} at {}, {}",
+                self.gig.artist_name.clone().unwrap_or_default(),
+                self.gig.venue_name.clone().unwrap_or_default(),
+                self.gig.date
+            ))
+            .title_bottom("a: Add  e: Edit  d: Remove  J/K: Move  x: Encore  s: Save  Esc: Close");
+
+        if let Some(err) = self.error.clone() {
+            block = block.border_style(Style::new().red()).title_bottom(err);
+        }
+
+        let content_area = block.inner(mid_area);
+
+        frame.render_widget(Clear {}, mid_area);
+        frame.render_widget(block, mid_area);
+
+        let [table_area, entry_area] = Layout::vertical(vec![
+            Constraint::Fill(1),
+            Constraint::Length(if self.entry.is_some() { 3 } else { 0 }),
+        ])
+        .areas(content_area);
+
+        if self.setlist.songs.is_empty() {
+            frame.render_widget(
+                Line::from("No songs yet, press a to add one")
+                    .dark_gray()
+                    .centered(),
+                table_area,
+            );
+        } else {
+            self.render_songs(frame, table_area);
+        }
+
+        if let Some(entry) = self.entry.as_mut() {
+            let [title_area, cover_of_area, notes_area] = Layout::horizontal(vec![
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ])
+            .areas(entry_area);
+
+            entry.title.render(frame, title_area);
+            entry.cover_of.render(frame, cover_of_area);
+            entry.notes.render(frame, notes_area);
+        }
+    }
+
+    fn render_songs(&mut self, frame: &mut Frame, area: Rect) {
+        let mut main_set = 0;
+        let mut encore = 0;
+
+        // The encore is numbered on its own, e.g. E1, E2
+        let rows: Vec<Row> = self
+            .setlist
+            .songs
+            .iter()
+            .map(|song| {
+                let number = if song.encore {
+                    encore += 1;
+                    format!("E{}", encore)
+                } else {
+                    main_set += 1;
+                    main_set.to_string()
+                };
+
+                let row = Row::new([
+                    number,
+                    song.title.clone(),
+                    song.cover_of.clone().unwrap_or_default(),
+                    song.notes.clone().unwrap_or_default(),
+                ]);
+
+                if song.encore {
+                    row.magenta()
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Length(4),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["#", "Song", "Cover Of", "Notes"]).bold())
+        .row_highlight_style(Style::new().dark_gray().on_gray());
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -0,0 +1,141 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType},
+    Frame,
+};
+
+use crate::tag;
+
+pub enum TagInputEvent {
+    Escape,
+    Save,
+}
+
+// Tags are typed one at a time, `Enter` adds the tag and `Tab` completes a tag already in use
+pub struct TagInput<'a> {
+    title: &'a str,
+    tags: Vec<String>,
+    value: String,
+    // Tags other artists have, for completing
+    known: Vec<String>,
+    focused: bool,
+}
+
+impl<'a> TagInput<'a> {
+    pub fn new(title: &'a str, known: Vec<String>) -> Self {
+        Self {
+            title,
+            tags: Vec::new(),
+            value: String::new(),
+            known,
+            focused: false,
+        }
+    }
+
+    pub fn set_values(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    // Includes anything typed but not added yet
+    pub fn get_values(&self) -> Vec<String> {
+        let mut tags = self.tags.clone();
+        tags.push(self.value.clone());
+
+        tag::parse(&tags)
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    // The first known tag starting with what's been typed that the artist doesn't have yet
+    fn suggestion(&self) -> Option<&String> {
+        let typed = self.value.trim().to_lowercase();
+        if typed.is_empty() {
+            return None;
+        }
+
+        self.known.iter().find(|known| {
+            known.to_lowercase().starts_with(&typed)
+                && !self.tags.iter().any(|tag| tag.eq_ignore_ascii_case(known))
+        })
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<TagInputEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(TagInputEvent::Escape),
+                KeyCode::Enter => {
+                    if self.value.trim().is_empty() {
+                        return Some(TagInputEvent::Save);
+                    }
+
+                    self.tags = self.get_values();
+                    self.value.clear();
+                }
+                KeyCode::Tab => {
+                    if let Some(suggestion) = self.suggestion() {
+                        self.value = suggestion.clone();
+                    }
+                }
+                KeyCode::Backspace => {
+                    if self.value.is_empty() {
+                        self.tags.pop();
+                    } else {
+                        self.value.pop();
+                    }
+                }
+                KeyCode::Char(char) => {
+                    if key.modifiers == KeyModifiers::CONTROL {
+                        return None;
+                    }
+
+                    self.value.push(char);
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title_top(self.title);
+
+        if self.focused {
+            block = block.border_type(BorderType::Double);
+
+            if let Some(suggestion) = self.suggestion() {
+                block = block.title_bottom(
+                    Line::from(format!("Tab: {}", suggestion))
+                        .dark_gray()
+                        .italic(),
+                );
+            }
+        }
+
+        let content_area = block.inner(area);
+
+        let mut spans: Vec<Span> = self
+            .tags
+            .iter()
+            .flat_map(|tag| {
+                [
+                    Span::from(format!(" {} ", tag)).black().on_gray(),
+                    " ".into(),
+                ]
+            })
+            .collect();
+        spans.push(Span::from(self.value.as_str()));
+
+        frame.render_widget(block, area);
+        frame.render_widget(Line::from(spans), content_area);
+    }
+}
@@ -0,0 +1,196 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::{Block, BorderType, Paragraph},
+    Frame,
+};
+
+pub enum TextAreaEvent {
+    Escape,
+}
+
+// Multi-line text, Enter starts a new line so moving on to the next field is left to the form
+pub struct TextArea<'a> {
+    title: &'a str,
+    // Never empty, the cursor's column is counted in chars rather than bytes
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+    // The first line and column in view, kept so the cursor stays on screen
+    scroll: (usize, usize),
+    focused: bool,
+}
+
+impl<'a> TextArea<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+            scroll: (0, 0),
+            focused: false,
+        }
+    }
+
+    pub fn set_value(&mut self, value: String) {
+        self.lines = value.lines().map(String::from).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+
+        self.row = self.lines.len() - 1;
+        self.col = self.line_len();
+    }
+
+    pub fn get_value(&self) -> Option<String> {
+        let value = self.lines.join("\n");
+
+        if value.trim().is_empty() {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    // The byte offset of the cursor in the current line
+    fn byte_idx(&self) -> usize {
+        self.lines[self.row]
+            .char_indices()
+            .nth(self.col)
+            .map_or(self.lines[self.row].len(), |(idx, _)| idx)
+    }
+
+    fn insert(&mut self, char: char) {
+        let idx = self.byte_idx();
+        self.lines[self.row].insert(idx, char);
+        self.col += 1;
+    }
+
+    fn new_line(&mut self) {
+        let idx = self.byte_idx();
+        let rest = self.lines[self.row].split_off(idx);
+
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let idx = self.byte_idx();
+            self.lines[self.row].remove(idx);
+        } else if self.row > 0 {
+            // Joins the line onto the end of the one above
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let idx = self.byte_idx();
+            self.lines[self.row].remove(idx);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn move_to_row(&mut self, row: usize) {
+        self.row = row;
+        self.col = self.col.min(self.line_len());
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<TextAreaEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(TextAreaEvent::Escape),
+                KeyCode::Enter => self.new_line(),
+                KeyCode::Backspace => self.backspace(),
+                KeyCode::Delete => self.delete(),
+                KeyCode::Left => {
+                    if self.col > 0 {
+                        self.col -= 1;
+                    } else if self.row > 0 {
+                        self.row -= 1;
+                        self.col = self.line_len();
+                    }
+                }
+                KeyCode::Right => {
+                    if self.col < self.line_len() {
+                        self.col += 1;
+                    } else if self.row + 1 < self.lines.len() {
+                        self.row += 1;
+                        self.col = 0;
+                    }
+                }
+                KeyCode::Up if self.row > 0 => self.move_to_row(self.row - 1),
+                KeyCode::Down if self.row + 1 < self.lines.len() => self.move_to_row(self.row + 1),
+                KeyCode::Home => self.col = 0,
+                KeyCode::End => self.col = self.line_len(),
+                KeyCode::Char(char) => {
+                    if key.modifiers == KeyModifiers::CONTROL {
+                        return None;
+                    }
+
+                    self.insert(char);
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title_top(self.title);
+
+        if self.focused {
+            block = block.border_type(BorderType::Double);
+        }
+
+        let content_area = block.inner(area);
+        let height = usize::from(content_area.height.max(1));
+        let width = usize::from(content_area.width.max(1));
+
+        let (mut top, mut left) = self.scroll;
+        top = top.clamp(self.row.saturating_sub(height - 1), self.row);
+        left = left.clamp(self.col.saturating_sub(width - 1), self.col);
+        self.scroll = (top, left);
+
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| line.chars().skip(left).collect())
+            .collect();
+
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(lines.join("\n")).scroll((top as u16, 0)),
+            content_area,
+        );
+
+        if self.focused {
+            frame.set_cursor_position(Position::new(
+                content_area.x + (self.col - left) as u16,
+                content_area.y + (self.row - top) as u16,
+            ));
+        }
+    }
+}
@@ -32,6 +32,10 @@ impl<'a> TextInput<'a> {
         self.error = Some(err);
     }
 
+    pub fn set_value(&mut self, value: String) {
+        self.value = value;
+    }
+
     pub fn get_value(&self) -> Option<String> {
         if self.value.is_empty() {
             return None;
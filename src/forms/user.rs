@@ -0,0 +1,199 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{dataset::DataSet, error::Error, user::User};
+
+use super::textinput::{TextInput, TextInputEvent};
+
+pub enum UserSelectEvent {
+    Close,
+    // `None` shows everyone's gigs
+    Select(Option<User>),
+    Delete(User),
+}
+
+// A popup for switching whose gigs are shown, users can be added and deleted from it too
+pub struct UserSelect {
+    pool: Pool<Sqlite>,
+    users: Vec<User>,
+    active: Option<User>,
+
+    // The name of a user being added
+    adding: Option<TextInput<'static>>,
+    // The first row is everyone, followed by each user
+    state: ListState,
+}
+
+impl UserSelect {
+    pub async fn new(pool: Pool<Sqlite>, active: Option<User>) -> Result<Self, Error> {
+        let mut select = Self {
+            pool,
+            users: Vec::new(),
+            active,
+            adding: None,
+            state: ListState::default(),
+        };
+
+        select.load_users().await?;
+
+        let selected = select
+            .active
+            .as_ref()
+            .and_then(|active| {
+                select
+                    .users
+                    .iter()
+                    .position(|user| user.user_id == active.user_id)
+            })
+            .map_or(0, |idx| idx + 1);
+        select.state.select(Some(selected));
+
+        Ok(select)
+    }
+
+    async fn load_users(&mut self) -> Result<(), Error> {
+        self.users = User::load_all(&self.pool).await?;
+        self.users.sort_unstable();
+
+        Ok(())
+    }
+
+    pub async fn handle_event(&mut self, event: &Event) -> Result<Option<UserSelectEvent>, Error> {
+        if self.adding.is_some() {
+            self.handle_adding_event(event).await?;
+            return Ok(None);
+        }
+
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(UserSelectEvent::Close)),
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::Char('a') => {
+                    let mut input = TextInput::new("Name");
+                    input.focus();
+                    self.adding = Some(input);
+                }
+                KeyCode::Char('d') => {
+                    // Everyone can't be deleted
+                    if let Some(user) = self.selected() {
+                        return Ok(Some(UserSelectEvent::Delete(user)));
+                    }
+                }
+                KeyCode::Enter => return Ok(Some(UserSelectEvent::Select(self.selected()))),
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn selected(&self) -> Option<User> {
+        self.state
+            .selected()
+            .and_then(|idx| idx.checked_sub(1))
+            .and_then(|idx| self.users.get(idx))
+            .cloned()
+    }
+
+    async fn handle_adding_event(&mut self, event: &Event) -> Result<(), Error> {
+        let Some(input) = self.adding.as_mut() else {
+            return Ok(());
+        };
+
+        match input.handle_event(event) {
+            Some(TextInputEvent::Escape) => self.adding = None,
+            Some(TextInputEvent::Save) => {
+                let Some(name) = input.get_value() else {
+                    input.set_err("A user needs a name".into());
+                    return Ok(());
+                };
+
+                let name = name.trim().to_string();
+                if let Err(err) = User::save(User::new(name.clone()), &self.pool).await {
+                    input.set_err(err.to_string());
+                    return Ok(());
+                }
+
+                self.adding = None;
+                self.load_users().await?;
+
+                // Ready to switch to straight away
+                if let Some(idx) = self.users.iter().position(|user| user.name == name) {
+                    self.state.select(Some(idx + 1));
+                }
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, mid_col, _] = Layout::horizontal(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, mid_area, _] = Layout::vertical(vec![
+            Constraint::Fill(1),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ])
+        .areas(mid_col);
+
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .white()
+            .title("Whose Gigs")
+            .title_bottom("Enter: Switch  a: Add User  d: Delete User  Esc: Close");
+
+        let content_area = block.inner(mid_area);
+
+        frame.render_widget(Clear {}, mid_area);
+        frame.render_widget(block, mid_area);
+
+        let [list_area, adding_area] = Layout::vertical(vec![
+            Constraint::Fill(1),
+            Constraint::Length(if self.adding.is_some() { 3 } else { 0 }),
+        ])
+        .areas(content_area);
+
+        // Marks whose gigs are showing now
+        let marker = |active: bool| if active { "● " } else { "  " };
+
+        let mut items = vec![ListItem::new(format!(
+            "{}Everyone",
+            marker(self.active.is_none())
+        ))];
+        items.extend(self.users.iter().map(|user| {
+            ListItem::new(format!(
+                "{}{} ({} gigs)",
+                marker(
+                    self.active
+                        .as_ref()
+                        .is_some_and(|active| active.user_id == user.user_id)
+                ),
+                user.name,
+                user.gigs
+            ))
+        }));
+
+        frame.render_stateful_widget(
+            List::new(items).highlight_style(Style::new().dark_gray().on_gray()),
+            list_area,
+            &mut self.state,
+        );
+
+        if let Some(input) = self.adding.as_mut() {
+            input.render(frame, adding_area);
+        }
+    }
+}
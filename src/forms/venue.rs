@@ -17,6 +17,7 @@ use super::{
 pub struct VenueForm<'a> {
     pool: Pool<Sqlite>,
     current_field: AvField,
+    editing: Option<Venue>,
 
     name: TextInput<'a>,
     city: ListInput<'a, City>,
@@ -30,12 +31,23 @@ impl VenueForm<'_> {
         Ok(Self {
             pool,
             current_field: AvField::None,
+            editing: None,
             name: TextInput::new("Name"),
             city: list_input,
             save: SaveButton::new(),
         })
     }
 
+    pub async fn edit(pool: Pool<Sqlite>, venue: Venue) -> Result<Self, Error> {
+        let mut form = Self::new(pool).await?;
+
+        form.name.set_value(venue.name.clone());
+        form.city.select_where(|city| city.city_id == venue.city_id);
+        form.editing = Some(venue);
+
+        Ok(form)
+    }
+
     fn change_focus(&mut self, new_focus: AvField) {
         match self.current_field {
             AvField::Name => self.name.unfocus(),
@@ -163,7 +175,12 @@ impl VenueForm<'_> {
 
         let venue = Venue::new(artist_name, city.city_id);
 
-        match Venue::save(venue, &self.pool).await {
+        let result = match &self.editing {
+            Some(original) => Venue::update(original, venue, &self.pool).await,
+            None => Venue::save(venue, &self.pool).await,
+        };
+
+        match result {
             Ok(_) => Ok(None),
             Err(err) => Ok(Some(AvFieldError::Save(err.to_string()))),
         }
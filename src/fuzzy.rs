@@ -0,0 +1,94 @@
+use ratatui::{
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
+
+const MATCH_SCORE: i64 = 1;
+const CONSECUTIVE_BONUS: i64 = 5;
+const WORD_START_BONUS: i64 = 8;
+const GAP_PENALTY: i64 = 1;
+
+// Separates fields when matching across them, so a match can't run from one field into the next
+const FIELD_SEPARATOR: char = '\u{0}';
+
+pub struct FuzzyMatch {
+    pub score: i64,
+    // Char indices of the matched characters within each field
+    pub indices: Vec<Vec<usize>>,
+}
+
+// Matches every character of `pattern` in order, ignoring case and spaces, so "arctic mnkys"
+// finds "Arctic Monkeys". Matches at the start of words and runs of matching characters score
+// higher, skipped characters score lower. An empty pattern matches everything with a score of 0.
+pub fn fuzzy_match(pattern: &str, fields: &[String]) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let text: Vec<(usize, usize, char)> = fields
+        .iter()
+        .enumerate()
+        .flat_map(|(field, value)| {
+            value
+                .chars()
+                .enumerate()
+                .map(move |(idx, char)| (field, idx, char))
+                .chain([(field, usize::MAX, FIELD_SEPARATOR)])
+        })
+        .collect();
+
+    let mut indices = vec![Vec::new(); fields.len()];
+    let mut score = 0;
+    let mut position = 0;
+    let mut last_match: Option<usize> = None;
+
+    for wanted in pattern {
+        let found = (position..text.len()).find(|&idx| {
+            let (_, _, char) = text[idx];
+            char != FIELD_SEPARATOR && char.to_lowercase().eq([wanted])
+        })?;
+
+        let (field, idx, _) = text[found];
+        let previous = found.checked_sub(1).map(|prev| text[prev].2);
+
+        score += MATCH_SCORE;
+
+        if previous.is_none_or(|previous| !previous.is_alphanumeric()) {
+            score += WORD_START_BONUS;
+        }
+
+        match last_match {
+            Some(last) if last + 1 == found => score += CONSECUTIVE_BONUS,
+            Some(last) => score -= GAP_PENALTY * (found - last - 1) as i64,
+            None => score -= GAP_PENALTY * found as i64,
+        }
+
+        indices[field].push(idx);
+        last_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+pub fn highlight(text: &str, indices: &[usize]) -> Line<'static> {
+    if indices.is_empty() {
+        return Line::from(text.to_string());
+    }
+
+    let spans: Vec<Span> = text
+        .chars()
+        .enumerate()
+        .map(|(idx, char)| {
+            if indices.contains(&idx) {
+                Span::styled(char.to_string(), Style::new().yellow().underlined())
+            } else {
+                Span::raw(char.to_string())
+            }
+        })
+        .collect();
+
+    Line::from(spans)
+}
@@ -1,81 +1,522 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
-use ratatui::widgets::Row;
 use sqlx::{Pool, Sqlite};
 
-use crate::{act::Act, dataset::DataSet, date::Date, error::Error};
+use crate::{
+    act::Act,
+    artist::Artist,
+    database::Names,
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    date::Date,
+    error::Error,
+    event::{self, Event},
+    festival::{Festival, Stage},
+    person::Person,
+    price,
+    query::Filter,
+    rating, setlist,
+    user::{self, User},
+    venue::Venue,
+};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Gig {
-    artist_id: i64,
-    venue_id: i64,
+    pub event_id: i64,
+    pub artist_id: i64,
+    pub venue_id: i64,
     pub date: Date,
-    act: Act,
+    pub act: Act,
+    pub position: i64,
+    pub festival_id: Option<i64>,
+    pub stage_id: Option<i64>,
 
-    artist_name: Option<String>,
-    venue_name: Option<String>,
+    // The ticket is shared by every gig on the event's lineup, prices are in minor units
+    pub price: Option<i64>,
+    pub fees: Option<i64>,
+    pub currency: Option<String>,
+    pub ticket_type: Option<String>,
+
+    // Out of `rating::MAX`, given to each artist's set
+    pub rating: Option<i64>,
+    pub notes: Option<String>,
+
+    // Everyone who came along to the event
+    pub people: Vec<String>,
+    // The users who were at the gig
+    pub users: Vec<String>,
+    // The artist's tags
+    pub tags: Vec<String>,
+
+    pub artist_name: Option<String>,
+    pub venue_name: Option<String>,
+    pub city_id: Option<i64>,
+    pub city_name: Option<String>,
+    pub festival_name: Option<String>,
+    pub stage_name: Option<String>,
+}
+
+impl PartialOrd for Gig {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for Gig {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.date.cmp(&other.date)
+        // Keep each lineup in running order
+        self.date
+            .cmp(&other.date)
+            .then(self.event_id.cmp(&other.event_id))
+            .then(self.position.cmp(&other.position))
+    }
+}
+
+pub enum GigFieldError {
+    Artist(String),
+    Venue(String),
+    Date(String),
+    Act(String),
+    Festival(String),
+    Stage(String),
+    Price(String),
+    Fees(String),
+    Currency(String),
+    Rating(String),
+    Save(String),
+}
+
+impl From<GigFieldError> for Error {
+    fn from(value: GigFieldError) -> Self {
+        match value {
+            GigFieldError::Artist(err)
+            | GigFieldError::Venue(err)
+            | GigFieldError::Date(err)
+            | GigFieldError::Act(err)
+            | GigFieldError::Festival(err)
+            | GigFieldError::Stage(err)
+            | GigFieldError::Price(err)
+            | GigFieldError::Fees(err)
+            | GigFieldError::Currency(err)
+            | GigFieldError::Rating(err)
+            | GigFieldError::Save(err) => Error::Str(err),
+        }
     }
 }
 
 impl Gig {
     pub fn new(artist_id: i64, venue_id: i64, date: Date, act: Act) -> Self {
+        // The event and position are worked out when the gig is saved
         Self {
+            event_id: 0,
             artist_id,
             venue_id,
             date,
             act,
+            position: 0,
+            festival_id: None,
+            stage_id: None,
+            price: None,
+            fees: None,
+            currency: None,
+            ticket_type: None,
+            rating: None,
+            notes: None,
+            people: Vec::new(),
+            users: Vec::new(),
+            tags: Vec::new(),
             artist_name: None,
             venue_name: None,
+            city_id: None,
+            city_name: None,
+            festival_name: None,
+            stage_name: None,
+        }
+    }
+
+    pub fn validate(
+        artist: Option<Artist>,
+        venue: Option<Venue>,
+        date: Option<Date>,
+        act: Option<Act>,
+    ) -> Result<Self, GigFieldError> {
+        let artist_id = match artist {
+            Some(artist) => artist.artist_id,
+            None => {
+                return Err(GigFieldError::Artist(
+                    "Field \"Artist\" cannot be empty".into(),
+                ))
+            }
+        };
+
+        let venue_id = match venue {
+            Some(venue) => venue.venue_id,
+            None => {
+                return Err(GigFieldError::Venue(
+                    "Field \"Venue\" cannot be empty".into(),
+                ))
+            }
+        };
+
+        let date = match date {
+            Some(date) => date,
+            None => return Err(GigFieldError::Date("Field \"Date\" cannot be empty".into())),
+        };
+
+        let act = match act {
+            Some(act) => act,
+            None => return Err(GigFieldError::Act("Field \"Act\" cannot be empty".into())),
+        };
+
+        Ok(Gig::new(artist_id, venue_id, date, act))
+    }
+
+    // Attaches a validated gig to a festival, and optionally one of its stages
+    pub fn at_festival(
+        mut self,
+        festival: Option<Festival>,
+        stage: Option<Stage>,
+    ) -> Result<Self, GigFieldError> {
+        let Some(festival) = festival else {
+            if stage.is_some() {
+                return Err(GigFieldError::Stage(
+                    "Pick the festival the stage is at".into(),
+                ));
+            }
+
+            return Ok(self);
+        };
+
+        if festival.venue_id != self.venue_id {
+            return Err(GigFieldError::Festival(format!(
+                "{} is held at {}",
+                festival,
+                festival.venue_name.as_deref().unwrap_or_default()
+            )));
+        }
+
+        if !festival.runs_on(&self.date) {
+            return Err(GigFieldError::Festival(format!(
+                "{} runs {}",
+                festival,
+                festival.dates()
+            )));
+        }
+
+        if let Some(stage) = &stage {
+            if stage.festival_id != festival.festival_id {
+                return Err(GigFieldError::Stage(format!(
+                    "{} isn't a stage at {}",
+                    stage.name, festival
+                )));
+            }
+        }
+
+        self.festival_id = Some(festival.festival_id);
+        self.stage_id = stage.map(|stage| stage.stage_id);
+
+        Ok(self)
+    }
+
+    // Adds the optional ticket details, a currency is needed once there's a price or fees
+    pub fn with_ticket(
+        mut self,
+        price: Option<String>,
+        fees: Option<String>,
+        currency: Option<String>,
+        ticket_type: Option<String>,
+    ) -> Result<Self, GigFieldError> {
+        self.price = price
+            .map(|price| price::parse(&price))
+            .transpose()
+            .map_err(GigFieldError::Price)?;
+
+        self.fees = fees
+            .map(|fees| price::parse(&fees))
+            .transpose()
+            .map_err(GigFieldError::Fees)?;
+
+        // The currency field is filled in ahead of time, so it's only kept when something was paid
+        if self.price.is_some() || self.fees.is_some() {
+            self.currency = match currency {
+                Some(currency) => Some(currency.trim().to_uppercase()),
+                None => {
+                    return Err(GigFieldError::Currency(
+                        "Field \"Currency\" is needed with a price".into(),
+                    ))
+                }
+            };
+        }
+
+        self.ticket_type = ticket_type;
+
+        Ok(self)
+    }
+
+    // Adds the optional rating and notes, blank notes are left out
+    pub fn with_review(
+        mut self,
+        rating: Option<i64>,
+        notes: Option<String>,
+    ) -> Result<Self, GigFieldError> {
+        self.rating = rating
+            .map(rating::validate)
+            .transpose()
+            .map_err(GigFieldError::Rating)?;
+
+        self.notes = notes
+            .map(|notes| notes.trim().to_string())
+            .filter(|notes| !notes.is_empty());
+
+        Ok(self)
+    }
+
+    pub fn with_people(mut self, people: Vec<Person>) -> Self {
+        self.people = people.into_iter().map(|person| person.name).collect();
+
+        self
+    }
+
+    pub fn with_users(mut self, users: Vec<User>) -> Self {
+        self.users = users.into_iter().map(|user| user.name).collect();
+
+        self
+    }
+
+    pub fn has_ticket(&self) -> bool {
+        self.price.is_some() || self.fees.is_some() || self.ticket_type.is_some()
+    }
+
+    // What the ticket cost including fees, if anything was paid
+    pub fn cost(&self) -> Option<i64> {
+        match (self.price, self.fees) {
+            (None, None) => None,
+            (price, fees) => Some(price.unwrap_or_default() + fees.unwrap_or_default()),
         }
     }
 }
 
 impl DataSet for Gig {
+    const FILTERS: &'static [&'static str] = &[
+        "artist", "venue", "city", "festival", "act", "year", "date", "rating", "with", "tag",
+        "user",
+    ];
+
     async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
         Ok(sqlx::query_as!(
             Gig,
             r#"
-            SELECT 'g'.'artist_id', 'g'.'venue_id', 'g'.'date', 'g'.'act',
-                   'a'.'name' AS 'artist_name', 'v'.'name' AS 'venue_name'
+            SELECT 'g'.'event_id', 'g'.'artist_id', 'e'.'venue_id', 'e'.'date' AS "date: Date",
+                   'g'.'act', 'g'.'position',
+                   'e'.'festival_id' AS "festival_id?", 'g'.'stage_id' AS "stage_id?",
+                   'e'.'price' AS "price?", 'e'.'fees' AS "fees?",
+                   'e'.'currency' AS "currency?", 'e'.'ticket_type' AS "ticket_type?",
+                   'g'.'rating' AS "rating?", 'g'.'notes' AS "notes?",
+                   (SELECT json_group_array("name") FROM (
+                       SELECT "person"."name" FROM "attendance"
+                       INNER JOIN "person" ON "person"."person_id" = "attendance"."person_id"
+                       WHERE "attendance"."event_id" = "g"."event_id"
+                       ORDER BY "person"."name"
+                   )) AS "people!: Names",
+                   (SELECT json_group_array("name") FROM (
+                       SELECT "user"."name" FROM "user_gig"
+                       INNER JOIN "user" ON "user"."user_id" = "user_gig"."user_id"
+                       WHERE "user_gig"."event_id" = "g"."event_id"
+                         AND "user_gig"."artist_id" = "g"."artist_id"
+                       ORDER BY "user"."name"
+                   )) AS "users!: Names",
+                   (SELECT json_group_array("name") FROM (
+                       SELECT "tag"."name" FROM "artist_tag"
+                       INNER JOIN "tag" ON "tag"."tag_id" = "artist_tag"."tag_id"
+                       WHERE "artist_tag"."artist_id" = "g"."artist_id"
+                       ORDER BY "tag"."name" COLLATE NOCASE
+                   )) AS "tags!: Names",
+                   'a'.'name' AS 'artist_name', 'v'.'name' AS 'venue_name',
+                   'c'.'city_id' AS 'city_id', 'c'.'name' AS 'city_name',
+                   'f'.'name' AS "festival_name?", 's'.'name' AS "stage_name?"
             FROM 'gig' g
+            INNER JOIN 'event' e ON 'e'.'event_id' = 'g'.'event_id'
             INNER JOIN 'artist' a ON 'a'.'artist_id' = 'g'.'artist_id'
-            INNER JOIN 'venue' v ON 'v'.'venue_id' = 'g'.'venue_id'"#
+            INNER JOIN 'venue' v ON 'v'.'venue_id' = 'e'.'venue_id'
+            INNER JOIN 'city' c ON 'c'.'city_id' = 'v'.'city_id'
+            LEFT JOIN 'festival' f ON 'f'.'festival_id' = 'e'.'festival_id'
+            LEFT JOIN 'stage' s ON 's'.'stage_id' = 'g'.'stage_id'"#
         )
         .fetch_all(pool)
         .await?)
     }
 
     async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
-        sqlx::query("INSERT INTO \"gig\" (\"artist_id\", \"venue_id\", \"date\", \"act\") VALUES ($1, $2, $3, $4)")
+        Event::save_lineup(&val, vec![(val.artist_id, val.act)], pool).await
+    }
+
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        let event_id = event::find_or_create(&mut tx, val.venue_id, &val.date).await?;
+
+        if event_id == original.event_id {
+            event::set_festival(&mut tx, event_id, val.festival_id).await?;
+            event::set_ticket(&mut tx, event_id, &val).await?;
+            event::set_people(&mut tx, event_id, &val).await?;
+
+            sqlx::query(
+                r#"
+                UPDATE "gig"
+                SET "artist_id" = $1, "act" = $2, "stage_id" = $3, "rating" = $4, "notes" = $5
+                WHERE "event_id" = $6 AND "artist_id" = $7"#,
+            )
             .bind(val.artist_id)
-            .bind(val.venue_id)
-            .bind(val.date.to_string())
             .bind(val.act as i64)
-            .execute(pool).await?;
+            .bind(val.stage_id)
+            .bind(val.rating)
+            .bind(&val.notes)
+            .bind(original.event_id)
+            .bind(original.artist_id)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            // Moving to another night puts the artist at the end of that night's lineup, the
+            // setlist goes with them
+            event::add_details(&mut tx, event_id, &val).await?;
+            event::add_to_lineup(&mut tx, event_id, &val).await?;
+            setlist::move_songs(&mut tx, original, event_id, val.artist_id).await?;
+
+            sqlx::query(r#"DELETE FROM "gig" WHERE "event_id" = $1 AND "artist_id" = $2"#)
+                .bind(original.event_id)
+                .bind(original.artist_id)
+                .execute(&mut *tx)
+                .await?;
+
+            event::remove_empty(&mut tx).await?;
+        }
+
+        user::set_attended(&mut tx, event_id, &val).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            // The setlist and who was there are removed along with the gig
+            DeleteStrategy::Cascade => {}
+            DeleteStrategy::Reassign(replacement) => {
+                let from = (val.event_id, val.artist_id);
+                let to = (replacement.event_id, replacement.artist_id);
+
+                setlist::append_songs(&mut tx, from, to).await?;
+                user::move_attended(&mut tx, from, to).await?;
+            }
+        }
+
+        sqlx::query(r#"DELETE FROM "gig" WHERE "event_id" = $1 AND "artist_id" = $2"#)
+            .bind(val.event_id)
+            .bind(val.artist_id)
+            .execute(&mut *tx)
+            .await?;
+
+        event::remove_empty(&mut tx).await?;
+
+        tx.commit().await?;
 
         Ok(())
     }
 
-    fn contains(&self, val: String) -> bool {
-        let search = val.as_str().to_lowercase();
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let songs: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM "song" WHERE "event_id" = $1 AND "artist_id" = $2"#,
+        )
+        .bind(self.event_id)
+        .bind(self.artist_id)
+        .fetch_one(pool)
+        .await?;
+
+        let users: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM "user_gig" WHERE "event_id" = $1 AND "artist_id" = $2"#,
+        )
+        .bind(self.event_id)
+        .bind(self.artist_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(vec![
+            Dependent {
+                table: "songs",
+                count: songs,
+            },
+            Dependent {
+                table: "users' gigs",
+                count: users,
+            },
+        ])
+    }
 
-        self.artist_name
-            .as_ref()
-            .is_some_and(|name| name.to_lowercase().contains(&search))
-            || self
-                .venue_name
-                .as_ref()
-                .is_some_and(|name| name.to_lowercase().contains(&search))
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.artist_name.clone().unwrap_or_default(),
+            self.venue_name.clone().unwrap_or_default(),
+            self.date.to_string(),
+            self.act.to_string(),
+            self.rating.map(rating::stars).unwrap_or_default(),
+        ]
+    }
+
+    fn matches(&self, filter: &Filter) -> bool {
+        let contains = |name: &Option<String>, value: &String| {
+            name.as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(value))
+        };
+
+        match filter {
+            Filter::Artist(artist) => contains(&self.artist_name, artist),
+            Filter::Venue(venue) => contains(&self.venue_name, venue),
+            Filter::City(city) => contains(&self.city_name, city),
+            Filter::Festival(festival) => contains(&self.festival_name, festival),
+            Filter::Act(act) => &self.act == act,
+            Filter::Year(comparison, year) => comparison.compare(&self.date.year, year),
+            Filter::Date(comparison, date) => comparison.compare(&self.date, date),
+            Filter::With(person) => self
+                .people
+                .iter()
+                .any(|name| name.to_lowercase().contains(person)),
+            Filter::Tag(tag) => self
+                .tags
+                .iter()
+                .any(|name| name.to_lowercase().contains(tag)),
+            Filter::User(user) => self.users.iter().any(|name| name.to_lowercase() == *user),
+            // Unrated gigs never match
+            Filter::Rating(comparison, rating) => self
+                .rating
+                .is_some_and(|own| comparison.compare(&own, rating)),
+        }
     }
 
     fn key(&self) -> impl Ord + Clone {
         &self.date
     }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => self.artist_name.cmp(&other.artist_name),
+            1 => self.venue_name.cmp(&other.venue_name),
+            3 => self.act.cmp(&other.act),
+            4 => self.rating.cmp(&other.rating),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
 }
 
 impl Display for Gig {
@@ -83,14 +524,3 @@ impl Display for Gig {
         write!(f, "{} - {}", self.date, self.act)
     }
 }
-
-impl From<Gig> for Row<'_> {
-    fn from(value: Gig) -> Self {
-        Row::new(vec![
-            value.artist_name.unwrap(),
-            value.venue_name.unwrap(),
-            value.date.to_string(),
-            value.act.to_string(),
-        ])
-    }
-}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Wrap},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    act::Act, date::Date, error::Error, event::Event, gig::Gig, graphs::act_color, price, rating,
+    user::User,
+};
+
+pub struct GigDetail {
+    pub event_id: i64,
+    pub artist_id: i64,
+    pub date: Date,
+    pub act: Act,
+
+    pub artist_name: String,
+    pub artist_city: String,
+    pub venue_name: String,
+    pub venue_city: String,
+
+    // How many times the artist / venue had been seen up to and including this gig, by the user
+    // when there is one
+    pub artist_seen: i64,
+    pub venue_visits: i64,
+}
+
+impl GigDetail {
+    pub async fn load_all(pool: &Pool<Sqlite>, user_id: Option<i64>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            GigDetail,
+            r#"
+            SELECT 'g'.'event_id', 'g'.'artist_id', 'e'.'date' AS "date: Date", 'g'.'act',
+                   'a'.'name' AS "artist_name!", 'ac'.'name' AS "artist_city!",
+                   'v'.'name' AS "venue_name!", 'vc'.'name' AS "venue_city!",
+                   ROW_NUMBER() OVER (
+                       PARTITION BY 'g'.'artist_id' ORDER BY 'e'.'date', 'e'.'venue_id'
+                   ) AS "artist_seen!: i64",
+                   DENSE_RANK() OVER (
+                       PARTITION BY 'e'.'venue_id' ORDER BY 'e'.'date'
+                   ) AS "venue_visits!: i64"
+            FROM 'gig' g
+            INNER JOIN 'event' e ON 'e'.'event_id' = 'g'.'event_id'
+            INNER JOIN 'artist' a ON 'a'.'artist_id' = 'g'.'artist_id'
+            INNER JOIN 'city' ac ON 'ac'.'city_id' = 'a'.'city_id'
+            INNER JOIN 'venue' v ON 'v'.'venue_id' = 'e'.'venue_id'
+            INNER JOIN 'city' vc ON 'vc'.'city_id' = 'v'.'city_id'
+            WHERE $1 IS NULL OR EXISTS (
+                SELECT 1 FROM 'user_gig' ug
+                WHERE 'ug'.'event_id' = 'g'.'event_id' AND 'ug'.'artist_id' = 'g'.'artist_id'
+                  AND 'ug'.'user_id' = $1
+            )"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+}
+
+// 1st, 2nd, 3rd, 4th ... 11th, 12th, 13th ... 21st
+fn ordinal(n: i64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+// e.g. "45.00 GBP + 3.50 fees (Standing)"
+fn ticket(gig: &Gig) -> Option<String> {
+    let currency = gig.currency.clone().unwrap_or_default();
+    let mut parts = Vec::new();
+
+    if let Some(price) = gig.price {
+        parts.push(format!("{} {}", price::format(price), currency));
+    }
+    if let Some(fees) = gig.fees {
+        parts.push(format!("+ {} fees", price::format(fees)));
+    }
+    if let Some(ticket_type) = &gig.ticket_type {
+        parts.push(format!("({})", ticket_type));
+    }
+
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+pub struct GigDetailPane {
+    pool: Pool<Sqlite>,
+    // Counts are only over this user's gigs, everyone's when `None`
+    user: Option<User>,
+    details: HashMap<(i64, i64), GigDetail>,
+    lineups: HashMap<i64, Vec<Gig>>,
+}
+
+impl GigDetailPane {
+    pub async fn new(pool: Pool<Sqlite>) -> Result<Self, Error> {
+        let mut pane = Self {
+            pool,
+            user: None,
+            details: HashMap::new(),
+            lineups: HashMap::new(),
+        };
+
+        pane.reload_data().await?;
+
+        Ok(pane)
+    }
+
+    pub async fn reload_data(&mut self) -> Result<(), Error> {
+        let user_id = self.user.as_ref().map(|user| user.user_id);
+        self.details = GigDetail::load_all(&self.pool, user_id)
+            .await?
+            .into_iter()
+            .map(|detail| ((detail.event_id, detail.artist_id), detail))
+            .collect();
+
+        self.lineups = Event::load_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|event| (event.event_id, event.lineup))
+            .collect();
+
+        Ok(())
+    }
+
+    pub fn set_user(&mut self, user: Option<User>) {
+        self.user = user;
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, gig: Option<&Gig>) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Details");
+
+        let detail = gig.and_then(|gig| self.details.get(&(gig.event_id, gig.artist_id)));
+
+        let Some(detail) = detail else {
+            frame.render_widget(
+                Paragraph::new("Select a gig to see more")
+                    .dark_gray()
+                    .block(block),
+                area,
+            );
+            return;
+        };
+
+        let field = |name: &'static str, value: String| {
+            Line::from(vec![Span::from(name).dark_gray(), Span::from(value)])
+        };
+
+        let artist_seen = match detail.artist_seen {
+            1 => "First time seeing this artist".to_string(),
+            n => format!("{} time seeing this artist", ordinal(n)),
+        };
+
+        let venue_visits = match detail.venue_visits {
+            1 => "First gig at this venue".to_string(),
+            n => format!("{} gig at this venue", ordinal(n)),
+        };
+
+        let mut lines = vec![
+            Line::from(detail.artist_name.clone()).bold(),
+            field("From:  ", detail.artist_city.clone()),
+            field("Venue: ", detail.venue_name.clone()),
+            field("In:    ", detail.venue_city.clone()),
+            field(
+                "Date:  ",
+                format!("{} {}", detail.date.weekday(), detail.date),
+            ),
+            field("Act:   ", detail.act.to_string()),
+        ];
+
+        if let Some(ticket) = gig.and_then(ticket) {
+            lines.push(field("Paid:  ", ticket));
+        }
+
+        if let Some(gig) = gig.filter(|gig| !gig.people.is_empty()) {
+            lines.push(field("With:  ", gig.people.join(", ")));
+        }
+
+        if let Some(rating) = gig.and_then(|gig| gig.rating) {
+            lines.push(field("Rated: ", rating::stars(rating)));
+        }
+
+        if let Some(notes) = gig.and_then(|gig| gig.notes.as_ref()) {
+            lines.push(Line::default());
+            lines.extend(
+                notes
+                    .lines()
+                    .map(|line| Line::from(line.to_string()).italic()),
+            );
+        }
+
+        lines.extend([
+            Line::default(),
+            Line::from(artist_seen).magenta(),
+            Line::from(venue_visits).magenta(),
+        ]);
+
+        // Only worth listing when there was more than one artist on the night
+        if let Some(lineup) = gig
+            .and_then(|gig| self.lineups.get(&gig.event_id))
+            .filter(|lineup| lineup.len() > 1)
+        {
+            lines.push(Line::default());
+            lines.push(Line::from("Lineup").dark_gray());
+
+            for other in lineup {
+                let line = Line::from(vec![
+                    Span::from(other.artist_name.clone().unwrap_or_default()),
+                    Span::from(format!(" ({})", other.act)).fg(act_color(&other.act)),
+                ]);
+
+                lines.push(if other.artist_id == detail.artist_id {
+                    line.bold()
+                } else {
+                    line
+                });
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(block),
+            area,
+        );
+    }
+}
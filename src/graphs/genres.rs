@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::gig::Gig;
+
+use super::{chart_block, GraphView};
+
+const PALETTE: [Color; 6] = [
+    Color::Green,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+];
+
+const DEFAULT_TOP_N: usize = 4;
+
+// Gigs per year for the most seen genres, a gig counts once for each of its artist's tags
+pub struct GenresGraph {
+    top_n: usize,
+    gigs: Vec<Gig>,
+
+    // The top genres, most seen first
+    genres: Vec<String>,
+    // Indexed the same as `genres`
+    per_year: BTreeMap<u32, Vec<u64>>,
+}
+
+impl Default for GenresGraph {
+    fn default() -> Self {
+        Self {
+            top_n: DEFAULT_TOP_N,
+            gigs: Vec::new(),
+            genres: Vec::new(),
+            per_year: BTreeMap::new(),
+        }
+    }
+}
+
+impl GenresGraph {
+    fn count(&mut self) {
+        let mut totals: HashMap<&str, u64> = HashMap::new();
+        for gig in &self.gigs {
+            for tag in &gig.tags {
+                *totals.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut totals: Vec<(&str, u64)> = totals.into_iter().collect();
+        totals
+            .sort_unstable_by(|(l_name, l), (r_name, r)| r.cmp(l).then_with(|| l_name.cmp(r_name)));
+
+        self.genres = totals
+            .into_iter()
+            .take(self.top_n)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        self.per_year.clear();
+        for gig in &self.gigs {
+            let counts = self
+                .per_year
+                .entry(gig.date.year)
+                .or_insert_with(|| vec![0; self.genres.len()]);
+
+            for tag in &gig.tags {
+                if let Some(idx) = self.genres.iter().position(|genre| genre == tag) {
+                    counts[idx] += 1;
+                }
+            }
+        }
+    }
+}
+
+impl GraphView for GenresGraph {
+    fn title(&self) -> &'static str {
+        "Genres"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        self.gigs = gigs.to_vec();
+        self.count();
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                // Every genre needs its own colour
+                KeyCode::Char('k') => self.top_n = (self.top_n + 1).min(PALETTE.len()),
+                KeyCode::Char('j') => self.top_n = self.top_n.saturating_sub(1).max(1),
+                KeyCode::Char('r') => self.top_n = DEFAULT_TOP_N,
+                _ => return,
+            }
+
+            self.count();
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let groups: Vec<BarGroup> = self
+            .per_year
+            .iter()
+            .map(|(year, counts)| {
+                let bars: Vec<Bar> = counts
+                    .iter()
+                    .zip(PALETTE)
+                    .map(|(count, color)| {
+                        Bar::default()
+                            .value(*count)
+                            .style(color)
+                            .value_style(Style::new().black().bg(color))
+                    })
+                    .collect();
+
+                BarGroup::default()
+                    .label(Line::from(year.to_string()).centered())
+                    .bars(&bars)
+            })
+            .collect();
+
+        let mut legend: Vec<Span> = self
+            .genres
+            .iter()
+            .zip(PALETTE)
+            .map(|(genre, color)| Span::from(format!(" {} ", genre)).fg(color))
+            .collect();
+
+        if legend.is_empty() {
+            legend.push(Span::from(" No tagged artists yet ").dark_gray());
+        }
+
+        let mut chart = BarChart::default()
+            .bar_width(3)
+            .bar_gap(0)
+            .group_gap(2)
+            .block(
+                chart_block(format!("Top {} Genres per Year", self.top_n))
+                    .title_bottom(Line::from(legend).centered())
+                    .title_bottom(Line::from("j/k: Top N  r: Reset").right_aligned()),
+            );
+
+        for group in groups {
+            chart = chart.data(group);
+        }
+
+        frame.render_widget(chart, area);
+    }
+}
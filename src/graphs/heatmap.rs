@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    date::{is_leap_year, Date},
+    gig::Gig,
+};
+
+use super::{act_color, chart_block, GraphView};
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mon ", "    ", "Wed ", "    ", "Fri ", "    ", "Sun "];
+
+pub struct HeatmapGraph {
+    cursor: Date,
+    gigs: HashMap<Date, Vec<Gig>>,
+}
+
+impl Default for HeatmapGraph {
+    fn default() -> Self {
+        Self {
+            cursor: Date::today(),
+            gigs: HashMap::new(),
+        }
+    }
+}
+
+impl HeatmapGraph {
+    fn count(&self, date: &Date) -> usize {
+        self.gigs.get(date).map_or(0, Vec::len)
+    }
+
+    // Shades of green in the same steps GitHub uses for contributions
+    fn color(count: usize) -> Color {
+        match count {
+            0 => Color::DarkGray,
+            1 => Color::Rgb(14, 68, 41),
+            2 => Color::Rgb(0, 109, 50),
+            3 => Color::Rgb(38, 166, 65),
+            _ => Color::Rgb(57, 211, 83),
+        }
+    }
+
+    fn render_calendar(&self, frame: &mut Frame, area: Rect) {
+        let year = self.cursor.year;
+        let first = Date::new(year, 1, 1).expect("January 1st always exists");
+
+        // Columns are weeks starting on a Monday, so the grid starts in the previous year
+        let start = first.add_days(-(first.weekday() as i64));
+        let days = if is_leap_year(year) { 366 } else { 365 };
+        let weeks = (first.weekday() as usize + days).div_ceil(7);
+
+        let total: usize = self
+            .gigs
+            .iter()
+            .filter(|(date, _)| date.year == year)
+            .map(|(_, gigs)| gigs.len())
+            .sum();
+
+        let block = chart_block(format!("{} Gigs in {}", total, year))
+            .title_bottom(Line::from("h/l: Week  j/k: Day  H/L: Year  t: Today").centered());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Squares get a gap between them when there's room
+        let cell_width = if inner.width as usize >= 4 + weeks * 2 {
+            2
+        } else {
+            1
+        };
+
+        let mut months = vec![Span::raw("    ")];
+        let mut label_until = 0;
+        for week in 0..weeks {
+            let monday = start.add_days(week as i64 * 7);
+            let sunday = monday.add_days(6);
+
+            // Label the week each month starts in, unless the last label is still being drawn
+            if week >= label_until && sunday.year == year && sunday.date <= 7 {
+                let label = sunday.month.to_string();
+                label_until = week + label.len().div_ceil(cell_width);
+                months.push(Span::raw(format!(
+                    "{:<width$}",
+                    label,
+                    width = (label_until - week) * cell_width
+                )));
+            } else if week >= label_until {
+                months.push(Span::raw(" ".repeat(cell_width)));
+            }
+        }
+
+        let mut lines = vec![Line::from(months).dark_gray()];
+
+        for (weekday, label) in WEEKDAY_LABELS.iter().enumerate() {
+            let mut row = vec![Span::raw(*label).dark_gray()];
+
+            for week in 0..weeks {
+                let date = start.add_days((week * 7 + weekday) as i64);
+
+                if date.year != year {
+                    row.push(Span::raw(" ".repeat(cell_width)));
+                    continue;
+                }
+
+                let mut style = Style::new().fg(Self::color(self.count(&date)));
+                if date == self.cursor {
+                    style = style.bg(Color::White);
+                }
+
+                row.push(Span::styled("■", style));
+                if cell_width == 2 {
+                    row.push(Span::raw(" "));
+                }
+            }
+
+            lines.push(Line::from(row));
+        }
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn render_day(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(format!("{} {}", self.cursor.weekday(), self.cursor));
+
+        let lines: Vec<Line> = match self.gigs.get(&self.cursor) {
+            Some(gigs) => gigs
+                .iter()
+                .map(|gig| {
+                    Line::from(vec![
+                        Span::from(gig.artist_name.clone().unwrap_or_default()).bold(),
+                        Span::from(" at "),
+                        Span::from(gig.venue_name.clone().unwrap_or_default()),
+                        Span::from(format!(" ({})", gig.act)).fg(act_color(&gig.act)),
+                    ])
+                })
+                .collect(),
+            None => vec![Line::from("No gigs").dark_gray()],
+        };
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+impl GraphView for HeatmapGraph {
+    fn title(&self) -> &'static str {
+        "Calendar"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        self.gigs.clear();
+
+        for gig in gigs {
+            self.gigs
+                .entry(gig.date.clone())
+                .or_default()
+                .push(gig.clone());
+        }
+
+        for gigs in self.gigs.values_mut() {
+            gigs.sort_unstable_by(|l, r| l.artist_name.cmp(&r.artist_name));
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('h') => self.cursor = self.cursor.add_days(-7),
+                KeyCode::Char('l') => self.cursor = self.cursor.add_days(7),
+                KeyCode::Char('k') => self.cursor = self.cursor.add_days(-1),
+                KeyCode::Char('j') => self.cursor = self.cursor.add_days(1),
+                KeyCode::Char('H') => self.cursor = self.cursor.add_months(-12),
+                KeyCode::Char('L') => self.cursor = self.cursor.add_months(12),
+                KeyCode::Char('t') => self.cursor = Date::today(),
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // Month labels and a row per weekday, plus the borders
+        let [calendar_area, day_area] =
+            Layout::vertical([Constraint::Length(10), Constraint::Fill(1)]).areas(area);
+
+        self.render_calendar(frame, calendar_area);
+        self.render_day(frame, day_area);
+    }
+}
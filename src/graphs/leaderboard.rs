@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Direction, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::{act::Act, gig::Gig};
+
+use super::{act_color, chart_block, GraphView, ACTS};
+
+const DEFAULT_TOP_N: usize = 10;
+
+// The event or the artist which is counted once per name
+type Visit = (Option<i64>, Option<i64>);
+
+#[derive(Clone, Copy)]
+pub enum Ranking {
+    Artists,
+    Venues,
+    Cities,
+    // Counts every artist seen at each festival, once however many days they played
+    Festivals,
+    // Everyone who came along counts the night once
+    People,
+}
+
+impl Ranking {
+    fn names(self, gig: &Gig) -> Vec<&str> {
+        match self {
+            Ranking::Artists => gig.artist_name.as_deref().into_iter().collect(),
+            Ranking::Venues => gig.venue_name.as_deref().into_iter().collect(),
+            Ranking::Cities => gig.city_name.as_deref().into_iter().collect(),
+            Ranking::Festivals => gig.festival_name.as_deref().into_iter().collect(),
+            Ranking::People => gig.people.iter().map(String::as_str).collect(),
+        }
+    }
+
+    // Nights are counted once however many acts were on
+    fn visit(self, gig: &Gig) -> Visit {
+        match self {
+            Ranking::Festivals => (None, Some(gig.artist_id)),
+            _ => (Some(gig.event_id), None),
+        }
+    }
+}
+
+pub struct Leaderboard {
+    ranking: Ranking,
+    top_n: usize,
+    act: Option<Act>,
+
+    // Inclusive range of years to count, `None` means unbounded
+    from: Option<u32>,
+    to: Option<u32>,
+
+    gigs: Vec<Gig>,
+    // Counts indexed by `Act` then the total, sorted by the most seen first. The total can be
+    // less than the sum when a night had more than one act on
+    rows: Vec<(String, [u64; 3], u64)>,
+}
+
+impl Leaderboard {
+    pub fn new(ranking: Ranking) -> Self {
+        Self {
+            ranking,
+            top_n: DEFAULT_TOP_N,
+            act: None,
+            from: None,
+            to: None,
+            gigs: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn years(&self) -> Option<(u32, u32)> {
+        let first = self.gigs.iter().map(|gig| gig.date.year).min()?;
+        let last = self.gigs.iter().map(|gig| gig.date.year).max()?;
+
+        Some((first, last))
+    }
+
+    fn rank(&mut self) {
+        let mut visits: HashMap<&str, [HashSet<Visit>; 3]> = HashMap::new();
+
+        let gigs = self.gigs.iter().filter(|gig| {
+            self.from.is_none_or(|from| gig.date.year >= from)
+                && self.to.is_none_or(|to| gig.date.year <= to)
+        });
+
+        for gig in gigs {
+            let visit = self.ranking.visit(gig);
+
+            for name in self.ranking.names(gig) {
+                visits.entry(name).or_default()[gig.act as usize].insert(visit);
+            }
+        }
+
+        let mut rows: Vec<(String, [u64; 3], u64)> = visits
+            .into_iter()
+            .map(|(name, visits)| {
+                let counts = visits.each_ref().map(|visits| visits.len() as u64);
+                let total = match self.act {
+                    Some(act) => counts[act as usize],
+                    None => visits.iter().flatten().collect::<HashSet<_>>().len() as u64,
+                };
+
+                (name.to_string(), counts, total)
+            })
+            .filter(|(.., total)| *total > 0)
+            .collect();
+
+        rows.sort_unstable_by(|(l_name, _, l), (r_name, _, r)| {
+            r.cmp(l).then_with(|| l_name.cmp(r_name))
+        });
+
+        self.rows = rows;
+    }
+
+    // Moves one end of the range by a year, keeping it within the years there are gigs for
+    fn shift(&mut self, start: bool, by: i64) {
+        let Some((first, last)) = self.years() else {
+            return;
+        };
+
+        let from = self.from.unwrap_or(first);
+        let to = self.to.unwrap_or(last);
+
+        if start {
+            let from = (i64::from(from) + by).clamp(i64::from(first), i64::from(to)) as u32;
+            self.from = (from != first).then_some(from);
+        } else {
+            let to = (i64::from(to) + by).clamp(i64::from(from), i64::from(last)) as u32;
+            self.to = (to != last).then_some(to);
+        }
+    }
+
+    fn heading(&self) -> String {
+        let mut heading = match self.ranking {
+            Ranking::Artists => format!("Top {} Artists", self.top_n),
+            Ranking::Venues => format!("Top {} Venues", self.top_n),
+            Ranking::Cities => format!("Top {} Cities", self.top_n),
+            Ranking::Festivals => format!("Top {} Festivals by Artists Seen", self.top_n),
+            Ranking::People => format!("Top {} People to Go With", self.top_n),
+        };
+
+        if let Some((first, last)) = self.years() {
+            let from = self.from.unwrap_or(first);
+            let to = self.to.unwrap_or(last);
+
+            if from == to {
+                heading.push_str(&format!(" in {}", from));
+            } else {
+                heading.push_str(&format!(", {} - {}", from, to));
+            }
+        }
+
+        if let Some(act) = self.act {
+            heading.push_str(&format!(" ({})", act));
+        }
+
+        heading
+    }
+}
+
+impl GraphView for Leaderboard {
+    fn title(&self) -> &'static str {
+        match self.ranking {
+            Ranking::Artists => "Top Artists",
+            Ranking::Venues => "Top Venues",
+            Ranking::Cities => "Top Cities",
+            Ranking::Festivals => "Top Festivals",
+            Ranking::People => "Top People",
+        }
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        self.gigs = gigs.to_vec();
+
+        // Drop any end of the range that no longer has gigs in it
+        if let Some((first, last)) = self.years() {
+            self.from = self.from.filter(|from| (first..=last).contains(from));
+            self.to = self.to.filter(|to| (first..=last).contains(to));
+        }
+
+        self.rank();
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('k') => self.top_n += 1,
+                KeyCode::Char('j') => self.top_n = self.top_n.saturating_sub(1).max(1),
+                KeyCode::Char('h') => self.shift(true, -1),
+                KeyCode::Char('l') => self.shift(true, 1),
+                KeyCode::Char('H') => self.shift(false, -1),
+                KeyCode::Char('L') => self.shift(false, 1),
+                KeyCode::Char('a') => {
+                    self.act = match self.act {
+                        None => Some(Act::Main),
+                        Some(Act::Main) => Some(Act::Support),
+                        Some(Act::Support) => Some(Act::Shared),
+                        Some(Act::Shared) => None,
+                    }
+                }
+                KeyCode::Char('r') => {
+                    self.top_n = DEFAULT_TOP_N;
+                    self.act = None;
+                    self.from = None;
+                    self.to = None;
+                }
+                _ => return,
+            }
+
+            self.rank();
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let bars: Vec<Bar> = self
+            .rows
+            .iter()
+            .take(self.top_n)
+            .map(|(name, counts, total)| {
+                // Break the total down by act, unless only one act is being counted
+                let breakdown: Vec<String> = ACTS
+                    .iter()
+                    .filter(|act| self.act.is_none() && counts[**act as usize] > 0)
+                    .map(|act| format!("{} {}", counts[*act as usize], act))
+                    .collect();
+
+                let text = match breakdown.len() {
+                    0 | 1 => total.to_string(),
+                    _ => format!("{} ({})", total, breakdown.join(", ")),
+                };
+
+                let color = act_color(&self.act.unwrap_or(Act::Main));
+
+                Bar::default()
+                    .label(name.clone().into())
+                    .value(*total)
+                    .text_value(text)
+                    .style(color)
+                    .value_style(Style::new().black().bg(color))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .data(BarGroup::default().bars(&bars))
+            .block(chart_block(self.heading()).title_bottom(
+                Line::from("j/k: Top N  h/l: From  H/L: To  a: Act  r: Reset").centered(),
+            ));
+
+        frame.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,49 @@
+use crossterm::event::Event;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType},
+    Frame,
+};
+
+use crate::{act::Act, gig::Gig, setlist::PlayedSong};
+
+pub mod genres;
+pub mod heatmap;
+pub mod leaderboard;
+pub mod month;
+pub mod ratings;
+pub mod songs;
+pub mod spend;
+pub mod year;
+
+pub const ACTS: [Act; 3] = [Act::Main, Act::Support, Act::Shared];
+
+// A single view in the graph column, `compute` is called whenever the gigs change and should do
+// all of the heavy lifting so that `render` can stay cheap
+pub trait GraphView {
+    fn title(&self) -> &'static str;
+    fn compute(&mut self, gigs: &[Gig]);
+    fn render(&mut self, frame: &mut Frame, area: Rect);
+
+    fn handle_event(&mut self, _event: &Event) {}
+
+    // Called alongside `compute`, only views about setlists need it
+    fn compute_songs(&mut self, _songs: &[PlayedSong]) {}
+}
+
+pub fn chart_block<'a>(title: impl Into<Line<'a>>) -> Block<'a> {
+    Block::bordered()
+        .title(title.into().white().bold().centered())
+        .border_type(BorderType::Double)
+        .border_style(Style::default().magenta())
+}
+
+pub fn act_color(act: &Act) -> Color {
+    match act {
+        Act::Main => Color::Green,
+        Act::Support => Color::Cyan,
+        Act::Shared => Color::Yellow,
+    }
+}
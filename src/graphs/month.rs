@@ -0,0 +1,45 @@
+use ratatui::{
+    layout::Rect,
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::{date::MONTHS, gig::Gig};
+
+use super::{chart_block, GraphView};
+
+#[derive(Default)]
+pub struct MonthGraph {
+    counts: [u64; 12],
+}
+
+impl GraphView for MonthGraph {
+    fn title(&self) -> &'static str {
+        "By Month"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        self.counts = [0; 12];
+
+        for gig in gigs {
+            if let Some(idx) = MONTHS.iter().position(|month| month == &gig.date.month) {
+                self.counts[idx] += 1;
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let dataset: Vec<Bar> = MONTHS
+            .iter()
+            .zip(self.counts)
+            .map(|(month, count)| Bar::default().value(count).label(month.to_string().into()))
+            .collect();
+
+        let chart = BarChart::default()
+            .bar_width(3)
+            .data(BarGroup::default().bars(&dataset))
+            .block(chart_block("Gig Chart"));
+
+        frame.render_widget(chart, area);
+    }
+}
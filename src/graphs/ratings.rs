@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Row, Table, TableState},
+    Frame,
+};
+
+use crate::{gig::Gig, rating};
+
+use super::{chart_block, GraphView};
+
+#[derive(Clone, Copy, Default)]
+enum Showing {
+    #[default]
+    Gigs,
+    Artists,
+    Venues,
+}
+
+impl Showing {
+    fn next(self) -> Self {
+        match self {
+            Showing::Gigs => Showing::Artists,
+            Showing::Artists => Showing::Venues,
+            Showing::Venues => Showing::Gigs,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Showing::Gigs => "Best Rated Gigs",
+            Showing::Artists => "Average Rating per Artist",
+            Showing::Venues => "Average Rating per Venue",
+        }
+    }
+}
+
+// The average of every rated gig with the same name
+struct Average {
+    name: String,
+    total: i64,
+    rated: i64,
+}
+
+impl Average {
+    fn value(&self) -> f64 {
+        self.total as f64 / self.rated as f64
+    }
+}
+
+// Only gigs with a rating are counted, unrated gigs don't drag an average down
+#[derive(Default)]
+pub struct RatingsView {
+    showing: Showing,
+    state: TableState,
+
+    // Best first
+    gigs: Vec<Gig>,
+    artists: Vec<Average>,
+    venues: Vec<Average>,
+}
+
+fn averages<'a>(gigs: &'a [Gig], name: impl Fn(&'a Gig) -> Option<&'a String>) -> Vec<Average> {
+    let mut averages: HashMap<&String, Average> = HashMap::new();
+
+    for gig in gigs {
+        if let (Some(name), Some(rating)) = (name(gig), gig.rating) {
+            let average = averages.entry(name).or_insert_with(|| Average {
+                name: name.clone(),
+                total: 0,
+                rated: 0,
+            });
+
+            average.total += rating;
+            average.rated += 1;
+        }
+    }
+
+    let mut averages: Vec<Average> = averages.into_values().collect();
+    averages.sort_unstable_by(|l, r| {
+        r.value()
+            .total_cmp(&l.value())
+            .then_with(|| r.rated.cmp(&l.rated))
+            .then_with(|| l.name.cmp(&r.name))
+    });
+
+    averages
+}
+
+impl RatingsView {
+    fn len(&self) -> usize {
+        match self.showing {
+            Showing::Gigs => self.gigs.len(),
+            Showing::Artists => self.artists.len(),
+            Showing::Venues => self.venues.len(),
+        }
+    }
+}
+
+impl GraphView for RatingsView {
+    fn title(&self) -> &'static str {
+        "Best Rated"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        let mut rated: Vec<Gig> = gigs
+            .iter()
+            .filter(|gig| gig.rating.is_some())
+            .cloned()
+            .collect();
+
+        // Most recent first between gigs with the same rating
+        rated.sort_unstable_by(|l, r| r.rating.cmp(&l.rating).then_with(|| r.cmp(l)));
+
+        self.artists = averages(&rated, |gig| gig.artist_name.as_ref());
+        self.venues = averages(&rated, |gig| gig.venue_name.as_ref());
+        self.gigs = rated;
+
+        if self.state.selected().is_some_and(|idx| idx >= self.len()) {
+            self.state.select(None);
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('v') => {
+                    self.showing = self.showing.next();
+                    self.state.select(None);
+                }
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let block = chart_block(self.showing.name())
+            .title_bottom(Line::from("v: Gigs/Artists/Venues  j/k: Scroll").centered());
+
+        if self.gigs.is_empty() {
+            frame.render_widget(
+                Line::from("No rated gigs yet").dark_gray().centered(),
+                block.inner(area),
+            );
+            frame.render_widget(block, area);
+            return;
+        }
+
+        let (header, rows, widths) = match self.showing {
+            Showing::Gigs => (
+                Row::new(["Date", "Artist", "Venue", "Rating"]),
+                self.gigs
+                    .iter()
+                    .map(|gig| {
+                        Row::new([
+                            gig.date.to_string(),
+                            gig.artist_name.clone().unwrap_or_default(),
+                            gig.venue_name.clone().unwrap_or_default(),
+                            gig.rating.map(rating::stars).unwrap_or_default(),
+                        ])
+                    })
+                    .collect::<Vec<Row>>(),
+                vec![
+                    Constraint::Length(12),
+                    Constraint::Fill(1),
+                    Constraint::Fill(1),
+                    Constraint::Length(8),
+                ],
+            ),
+            Showing::Artists | Showing::Venues => {
+                let averages = match self.showing {
+                    Showing::Artists => &self.artists,
+                    _ => &self.venues,
+                };
+
+                (
+                    Row::new(["Name", "Average", "Rated Gigs"]),
+                    averages
+                        .iter()
+                        .map(|average| {
+                            Row::new([
+                                average.name.clone(),
+                                format!("{:.1} / {}", average.value(), rating::MAX),
+                                average.rated.to_string(),
+                            ])
+                        })
+                        .collect(),
+                    vec![
+                        Constraint::Fill(1),
+                        Constraint::Length(10),
+                        Constraint::Length(10),
+                    ],
+                )
+            }
+        };
+
+        let table = Table::new(rows, widths)
+            .header(header.bold())
+            .style(Style::new().white())
+            .row_highlight_style(Style::new().dark_gray().on_gray())
+            .block(block);
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
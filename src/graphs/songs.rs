@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Direction, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::{gig::Gig, setlist::PlayedSong};
+
+use super::{chart_block, GraphView};
+
+const DEFAULT_TOP_N: usize = 10;
+
+#[derive(Clone, Copy, Default)]
+enum Showing {
+    #[default]
+    Songs,
+    Artists,
+}
+
+// Built from the setlists, so only gigs with one written down are counted
+pub struct SongsView {
+    showing: Showing,
+    top_n: usize,
+
+    // Most heard first, the song's name and its artist
+    songs: Vec<(String, String, u64)>,
+    // Songs heard and how many of them were different, most heard first
+    artists: Vec<(String, u64, u64)>,
+}
+
+impl Default for SongsView {
+    fn default() -> Self {
+        Self {
+            showing: Showing::default(),
+            top_n: DEFAULT_TOP_N,
+            songs: Vec::new(),
+            artists: Vec::new(),
+        }
+    }
+}
+
+impl GraphView for SongsView {
+    fn title(&self) -> &'static str {
+        "Songs"
+    }
+
+    fn compute(&mut self, _gigs: &[Gig]) {}
+
+    fn compute_songs(&mut self, songs: &[PlayedSong]) {
+        // The same title by two artists is two different songs
+        let mut heard: HashMap<(&String, String), u64> = HashMap::new();
+        for song in songs {
+            *heard.entry((&song.artist_name, song.name())).or_default() += 1;
+        }
+
+        let mut artists: HashMap<&String, (u64, u64)> = HashMap::new();
+        for ((artist, _), count) in &heard {
+            let (total, different) = artists.entry(artist).or_default();
+            *total += count;
+            *different += 1;
+        }
+
+        let mut songs: Vec<(String, String, u64)> = heard
+            .into_iter()
+            .map(|((artist, name), count)| (name, artist.clone(), count))
+            .collect();
+        songs.sort_unstable_by(|(l_name, l_artist, l), (r_name, r_artist, r)| {
+            r.cmp(l)
+                .then_with(|| l_name.cmp(r_name))
+                .then_with(|| l_artist.cmp(r_artist))
+        });
+
+        let mut artists: Vec<(String, u64, u64)> = artists
+            .into_iter()
+            .map(|(artist, (total, different))| (artist.clone(), total, different))
+            .collect();
+        artists.sort_unstable_by(|(l_name, l, _), (r_name, r, _)| {
+            r.cmp(l).then_with(|| l_name.cmp(r_name))
+        });
+
+        self.songs = songs;
+        self.artists = artists;
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('v') => {
+                    self.showing = match self.showing {
+                        Showing::Songs => Showing::Artists,
+                        Showing::Artists => Showing::Songs,
+                    }
+                }
+                KeyCode::Char('k') => self.top_n += 1,
+                KeyCode::Char('j') => self.top_n = self.top_n.saturating_sub(1).max(1),
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let footer = Line::from("v: Songs/Artists  j/k: Top N").centered();
+
+        let (heading, bars): (String, Vec<Bar>) = match self.showing {
+            Showing::Songs => (
+                format!("Top {} Most Heard Songs", self.top_n),
+                self.songs
+                    .iter()
+                    .take(self.top_n)
+                    .map(|(name, artist, count)| {
+                        Bar::default()
+                            .label(format!("{} - {}", name, artist).into())
+                            .value(*count)
+                            .style(Style::new().green())
+                            .value_style(Style::new().black().on_green())
+                    })
+                    .collect(),
+            ),
+            Showing::Artists => (
+                format!("Top {} Artists by Songs Heard", self.top_n),
+                self.artists
+                    .iter()
+                    .take(self.top_n)
+                    .map(|(artist, total, different)| {
+                        Bar::default()
+                            .label(artist.clone().into())
+                            .value(*total)
+                            .text_value(format!("{} ({} different)", total, different))
+                            .style(Style::new().cyan())
+                            .value_style(Style::new().black().on_cyan())
+                    })
+                    .collect(),
+            ),
+        };
+
+        if bars.is_empty() {
+            let block = chart_block(heading).title_bottom(footer);
+            frame.render_widget(
+                Line::from("No setlists yet, press Enter on a gig to add one")
+                    .dark_gray()
+                    .centered(),
+                block.inner(area),
+            );
+            frame.render_widget(block, area);
+            return;
+        }
+
+        let chart = BarChart::default()
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .data(BarGroup::default().bars(&bars))
+            .block(chart_block(heading).title_bottom(footer));
+
+        frame.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::{gig::Gig, price};
+
+use super::{chart_block, GraphView};
+
+#[derive(Clone, Copy, Default)]
+enum Metric {
+    #[default]
+    Total,
+    PerGig,
+    PerArtist,
+}
+
+impl Metric {
+    fn next(self) -> Self {
+        match self {
+            Metric::Total => Metric::PerGig,
+            Metric::PerGig => Metric::PerArtist,
+            Metric::PerArtist => Metric::Total,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Total => "Total Spend",
+            Metric::PerGig => "Average Price per Gig",
+            Metric::PerArtist => "Cost per Artist Seen",
+        }
+    }
+}
+
+// Everything paid in one currency during a year
+#[derive(Default, Clone, Copy)]
+struct Spend {
+    total: i64,
+    // Events with a ticket, and how many artists were seen at them
+    gigs: i64,
+    artists: i64,
+}
+
+impl Spend {
+    fn add(&mut self, other: Spend) {
+        self.total += other.total;
+        self.gigs += other.gigs;
+        self.artists += other.artists;
+    }
+
+    fn value(&self, metric: Metric) -> i64 {
+        match metric {
+            Metric::Total => self.total,
+            Metric::PerGig => self.total.checked_div(self.gigs).unwrap_or_default(),
+            Metric::PerArtist => self.total.checked_div(self.artists).unwrap_or_default(),
+        }
+    }
+}
+
+// Money can't be added up across currencies, so only one is shown at a time
+#[derive(Default)]
+pub struct SpendGraph {
+    metric: Metric,
+    // Most used first
+    currencies: Vec<String>,
+    current: usize,
+
+    per_year: HashMap<String, BTreeMap<u32, Spend>>,
+}
+
+impl SpendGraph {
+    fn years(&self) -> Option<&BTreeMap<u32, Spend>> {
+        self.per_year.get(self.currencies.get(self.current)?)
+    }
+}
+
+impl GraphView for SpendGraph {
+    fn title(&self) -> &'static str {
+        "Spending"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        // The ticket is shared by the whole lineup, so it's only counted once per event
+        let mut events: HashMap<i64, (&Gig, i64)> = HashMap::new();
+        for gig in gigs.iter().filter(|gig| gig.cost().is_some()) {
+            events.entry(gig.event_id).or_insert((gig, 0)).1 += 1;
+        }
+
+        self.per_year.clear();
+        for (gig, artists) in events.into_values() {
+            let spend = Spend {
+                total: gig.cost().unwrap_or_default(),
+                gigs: 1,
+                artists,
+            };
+
+            self.per_year
+                .entry(gig.currency.clone().unwrap_or_default())
+                .or_default()
+                .entry(gig.date.year)
+                .or_default()
+                .add(spend);
+        }
+
+        let current = self.currencies.get(self.current).cloned();
+
+        let mut currencies: Vec<(String, i64)> = self
+            .per_year
+            .iter()
+            .map(|(currency, years)| (currency.clone(), years.values().map(|y| y.gigs).sum()))
+            .collect();
+        currencies
+            .sort_unstable_by(|(l_name, l), (r_name, r)| r.cmp(l).then_with(|| l_name.cmp(r_name)));
+
+        self.currencies = currencies.into_iter().map(|(name, _)| name).collect();
+        self.current = current
+            .and_then(|current| self.currencies.iter().position(|name| *name == current))
+            .unwrap_or_default();
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('m') => self.metric = self.metric.next(),
+                KeyCode::Char('c') if !self.currencies.is_empty() => {
+                    self.current = (self.current + 1) % self.currencies.len();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let footer = Line::from("m: Metric  c: Currency").centered();
+
+        let Some(years) = self.years() else {
+            let block = chart_block(self.metric.name()).title_bottom(footer);
+            frame.render_widget(
+                Line::from("No gigs with a ticket price yet")
+                    .dark_gray()
+                    .centered(),
+                block.inner(area),
+            );
+            frame.render_widget(block, area);
+            return;
+        };
+
+        let mut overall = Spend::default();
+        for spend in years.values() {
+            overall.add(*spend);
+        }
+
+        let heading = format!(
+            "{} ({}): {}",
+            self.metric.name(),
+            self.currencies[self.current],
+            price::format(overall.value(self.metric))
+        );
+
+        let bars: Vec<Bar> = years
+            .iter()
+            .map(|(year, spend)| {
+                let value = spend.value(self.metric);
+
+                Bar::default()
+                    .label(Line::from(year.to_string()))
+                    .value(value.unsigned_abs())
+                    .text_value(price::format(value))
+                    .style(Style::new().green())
+                    .value_style(Style::new().black().on_green())
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .bar_width(8)
+            .bar_gap(2)
+            .data(BarGroup::default().bars(&bars))
+            .block(chart_block(heading).title_bottom(footer));
+
+        frame.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup},
+    Frame,
+};
+
+use crate::gig::Gig;
+
+use super::{act_color, chart_block, GraphView, ACTS};
+
+#[derive(Default)]
+pub struct YearGraph {
+    // Indexed by `Act`
+    per_year: BTreeMap<u32, [u64; 3]>,
+}
+
+impl GraphView for YearGraph {
+    fn title(&self) -> &'static str {
+        "By Year"
+    }
+
+    fn compute(&mut self, gigs: &[Gig]) {
+        self.per_year.clear();
+
+        for gig in gigs {
+            self.per_year.entry(gig.date.year).or_default()[gig.act as usize] += 1;
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let groups: Vec<BarGroup> = self
+            .per_year
+            .iter()
+            .map(|(year, counts)| {
+                let bars: Vec<Bar> = ACTS
+                    .iter()
+                    .map(|act| {
+                        Bar::default()
+                            .value(counts[*act as usize])
+                            .style(act_color(act))
+                            .value_style(Style::new().black().bg(act_color(act)))
+                    })
+                    .collect();
+
+                BarGroup::default()
+                    .label(Line::from(year.to_string()).centered())
+                    .bars(&bars)
+            })
+            .collect();
+
+        let legend: Vec<Span> = ACTS
+            .iter()
+            .map(|act| Span::from(format!(" {} ", act)).fg(act_color(act)))
+            .collect();
+
+        let mut chart = BarChart::default()
+            .bar_width(3)
+            .bar_gap(0)
+            .group_gap(2)
+            .block(chart_block("Gigs per Year").title_bottom(Line::from(legend).centered()));
+
+        for group in groups {
+            chart = chart.data(group);
+        }
+
+        frame.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,614 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::Read,
+};
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{
+    act::Act,
+    artist::Artist,
+    city::City,
+    dataset::DataSet,
+    date::{Date, DateError},
+    error::Error,
+    event,
+    export::Export,
+    festival::{self, Festival},
+    gig::Gig,
+    person::Person,
+    setlist::{Setlist, Song},
+    tag,
+    user::{self, User},
+    venue::Venue,
+};
+
+const COLUMNS: [&str; 5] = ["artist", "venue", "city", "date", "act"];
+
+pub enum SkipReason {
+    // The row couldn't be read at all, e.g. it has the wrong number of fields
+    Malformed(String),
+    MissingField(&'static str),
+    BadDate(DateError),
+    UnknownAct(String),
+    Unknown(&'static str, String),
+    // A field the gig form would have rejected, e.g. a price without a currency or a rating out of
+    // range
+    Invalid(String),
+    Duplicate,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Malformed(err) => write!(f, "{}", err),
+            SkipReason::MissingField(field) => write!(f, "Field \"{}\" cannot be empty", field),
+            SkipReason::BadDate(err) => write!(f, "{}", err),
+            SkipReason::UnknownAct(act) => write!(f, "Unknown act \"{}\"", act),
+            SkipReason::Unknown(kind, name) => write!(f, "Unknown {} \"{}\"", kind, name),
+            SkipReason::Invalid(err) => write!(f, "{}", err),
+            SkipReason::Duplicate => write!(f, "Gig already exists"),
+        }
+    }
+}
+
+pub struct SkippedRow {
+    pub row: u64,
+    pub reason: SkipReason,
+}
+
+#[derive(Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Imported {} gigs, skipped {} rows",
+            self.imported,
+            self.skipped.len()
+        )?;
+
+        for skipped in &self.skipped {
+            writeln!(f, "  row {}: {}", skipped.row, skipped.reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Names are matched case-insensitively, so "leeds" and "Leeds" are the same city
+struct Lookup {
+    cities: HashMap<String, i64>,
+    artists: HashMap<String, i64>,
+    venues: HashMap<String, i64>,
+    // Festival names can repeat from year to year, so they're matched by the dates they run too
+    festivals: Vec<Festival>,
+    // Attendance is saved by name, so this keeps the name each person has in the database
+    people: HashMap<String, String>,
+    users: HashMap<String, String>,
+    gigs: HashSet<(i64, i64, String)>,
+}
+
+impl Lookup {
+    async fn load(pool: &Pool<Sqlite>) -> Result<Self, Error> {
+        Ok(Self {
+            cities: City::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|city| (city.name.to_lowercase(), city.city_id))
+                .collect(),
+            artists: Artist::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|artist| (artist.name.to_lowercase(), artist.artist_id))
+                .collect(),
+            venues: Venue::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|venue| (venue.name.to_lowercase(), venue.venue_id))
+                .collect(),
+            festivals: Festival::load_all(pool).await?,
+            people: Person::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|person| (person.name.to_lowercase(), person.name))
+                .collect(),
+            users: User::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|user| (user.name.to_lowercase(), user.name))
+                .collect(),
+            gigs: Gig::load_all(pool)
+                .await?
+                .into_iter()
+                .map(|gig| (gig.artist_id, gig.venue_id, gig.date.to_string()))
+                .collect(),
+        })
+    }
+
+    async fn city_id(&mut self, conn: &mut SqliteConnection, name: &str) -> Result<i64, Error> {
+        if let Some(&city_id) = self.cities.get(&name.to_lowercase()) {
+            return Ok(city_id);
+        }
+
+        let city_id = insert_named(conn, "city", name, None).await?;
+        self.cities.insert(name.to_lowercase(), city_id);
+
+        Ok(city_id)
+    }
+
+    async fn artist_id(
+        &mut self,
+        conn: &mut SqliteConnection,
+        name: &str,
+        city_id: i64,
+    ) -> Result<i64, Error> {
+        if let Some(&artist_id) = self.artists.get(&name.to_lowercase()) {
+            return Ok(artist_id);
+        }
+
+        let artist_id = insert_named(conn, "artist", name, Some(city_id)).await?;
+        self.artists.insert(name.to_lowercase(), artist_id);
+
+        Ok(artist_id)
+    }
+
+    async fn venue_id(
+        &mut self,
+        conn: &mut SqliteConnection,
+        name: &str,
+        city_id: i64,
+    ) -> Result<i64, Error> {
+        if let Some(&venue_id) = self.venues.get(&name.to_lowercase()) {
+            return Ok(venue_id);
+        }
+
+        let venue_id = insert_named(conn, "venue", name, Some(city_id)).await?;
+        self.venues.insert(name.to_lowercase(), venue_id);
+
+        Ok(venue_id)
+    }
+
+    async fn festival_id(
+        &mut self,
+        conn: &mut SqliteConnection,
+        festival: Festival,
+    ) -> Result<i64, Error> {
+        let festival_id = festival::find_or_create(conn, &festival).await?;
+
+        if !self
+            .festivals
+            .iter()
+            .any(|known| known.festival_id == festival_id)
+        {
+            self.festivals.push(Festival {
+                festival_id,
+                ..festival
+            });
+        }
+
+        Ok(festival_id)
+    }
+
+    // New people are checked like the person form would, rather than inserted as they are
+    async fn person(
+        &mut self,
+        conn: &mut SqliteConnection,
+        name: &str,
+    ) -> Result<Result<Person, SkipReason>, Error> {
+        if let Some(known) = self.people.get(&name.to_lowercase()) {
+            return Ok(Ok(Person::new(known.clone())));
+        }
+
+        let person = Person::new(name.into());
+        if let Err(err) = person.check_name() {
+            return Ok(Err(SkipReason::Invalid(format!("{} \"{}\"", err, name))));
+        }
+
+        insert_named(conn, "person", name, None).await?;
+        self.people.insert(name.to_lowercase(), name.into());
+
+        Ok(Ok(person))
+    }
+
+    async fn user(
+        &mut self,
+        conn: &mut SqliteConnection,
+        name: &str,
+    ) -> Result<Result<User, SkipReason>, Error> {
+        if let Some(known) = self.users.get(&name.to_lowercase()) {
+            return Ok(Ok(User::new(known.clone())));
+        }
+
+        let user = User::new(name.into());
+        if let Err(err) = user.check_name() {
+            return Ok(Err(SkipReason::Invalid(format!("{} \"{}\"", err, name))));
+        }
+
+        insert_named(conn, "user", name, None).await?;
+        self.users.insert(name.to_lowercase(), name.into());
+
+        Ok(Ok(user))
+    }
+
+    // Attaches the gig to the festival running on its date, and the stage there if it has one
+    async fn at_festival(
+        &self,
+        conn: &mut SqliteConnection,
+        mut gig: Gig,
+        festival: &str,
+        stage: Option<&str>,
+    ) -> Result<Result<Gig, SkipReason>, Error> {
+        let Some(found) = self
+            .festivals
+            .iter()
+            .find(|known| known.name.eq_ignore_ascii_case(festival) && known.runs_on(&gig.date))
+        else {
+            return Ok(Err(SkipReason::Unknown("festival", festival.into())));
+        };
+
+        gig.festival_id = Some(found.festival_id);
+
+        if let Some(stage) = stage {
+            let stage_id: Option<i64> = sqlx::query_scalar(
+                r#"
+                SELECT "stage_id" FROM "stage"
+                WHERE "festival_id" = $1 AND "name" = $2 COLLATE NOCASE"#,
+            )
+            .bind(found.festival_id)
+            .bind(stage)
+            .fetch_optional(conn)
+            .await?;
+
+            let Some(stage_id) = stage_id else {
+                return Ok(Err(SkipReason::Unknown("stage", stage.into())));
+            };
+
+            gig.stage_id = Some(stage_id);
+        }
+
+        Ok(Ok(gig))
+    }
+
+    // Returns `Some` with the reason if the gig couldn't be inserted
+    async fn insert_gig(
+        &mut self,
+        conn: &mut SqliteConnection,
+        gig: Gig,
+        songs: Vec<Song>,
+    ) -> Result<Option<SkipReason>, Error> {
+        if !self
+            .gigs
+            .insert((gig.artist_id, gig.venue_id, gig.date.to_string()))
+        {
+            return Ok(Some(SkipReason::Duplicate));
+        }
+
+        let event_id = event::find_or_create(conn, gig.venue_id, &gig.date).await?;
+        event::add_details(conn, event_id, &gig).await?;
+        event::add_to_lineup(conn, event_id, &gig).await?;
+        if !gig.users.is_empty() {
+            user::set_attended(conn, event_id, &gig).await?;
+        }
+
+        Setlist {
+            event_id,
+            artist_id: gig.artist_id,
+            songs,
+        }
+        .insert(conn)
+        .await?;
+
+        Ok(None)
+    }
+}
+
+// Reads `artist,venue,city,date,act` rows, creating any cities, artists and venues that don't
+// exist yet. New artists are placed in the city of the row they first appear in.
+// Every insert happens in one transaction, rows which can't be imported are reported instead.
+// The gigs are recorded as `user`'s when there is one.
+pub async fn import_gigs(
+    reader: impl Read,
+    user: Option<&User>,
+    pool: &Pool<Sqlite>,
+) -> Result<ImportReport, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = reader.headers()?.clone();
+    let positions: Vec<usize> = COLUMNS
+        .iter()
+        .map(|column| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(column))
+                .ok_or_else(|| Error::Str(format!("Missing column \"{}\"", column)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut lookup = Lookup::load(pool).await?;
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await?;
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            // A file that can't be read at all still fails the import
+            Err(err) if err.is_io_error() => return Err(err.into()),
+            Err(err) => {
+                report.skipped.push(SkippedRow {
+                    row: err.position().map_or(0, |position| position.line()),
+                    reason: SkipReason::Malformed(match err.kind() {
+                        csv::ErrorKind::UnequalLengths {
+                            expected_len, len, ..
+                        } => format!("Expected {} fields, found {}", expected_len, len),
+                        _ => err.to_string(),
+                    }),
+                });
+                continue;
+            }
+        };
+
+        let row = record.position().map_or(0, |position| position.line());
+        let fields: Vec<&str> = positions
+            .iter()
+            .map(|&idx| record.get(idx).unwrap_or_default())
+            .collect();
+
+        if let Some(empty) = fields.iter().position(|field| field.is_empty()) {
+            report.skipped.push(SkippedRow {
+                row,
+                reason: SkipReason::MissingField(COLUMNS[empty]),
+            });
+            continue;
+        }
+
+        let [artist, venue, city, date, act] = fields[..] else {
+            unreachable!("There is a field for every column");
+        };
+
+        let (date, act) = match parse_date_and_act(date, act) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                report.skipped.push(SkippedRow { row, reason });
+                continue;
+            }
+        };
+
+        let city_id = lookup.city_id(&mut tx, city).await?;
+        let artist_id = lookup.artist_id(&mut tx, artist, city_id).await?;
+        let venue_id = lookup.venue_id(&mut tx, venue, city_id).await?;
+
+        match lookup
+            .insert_gig(
+                &mut tx,
+                Gig::new(artist_id, venue_id, date, act)
+                    .with_users(user.into_iter().cloned().collect()),
+                Vec::new(),
+            )
+            .await?
+        {
+            Some(reason) => report.skipped.push(SkippedRow { row, reason }),
+            None => report.imported += 1,
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+// Rebuilds a database from the document written by `export::export_json`.
+// Anything which already exists is matched by name, so importing the same export twice is safe.
+// Gigs which don't say who was there are recorded as `user`'s when there is one.
+pub async fn import_json(
+    reader: impl Read,
+    user: Option<&User>,
+    pool: &Pool<Sqlite>,
+) -> Result<ImportReport, Error> {
+    let export: Export = serde_json::from_reader(reader)?;
+
+    let mut lookup = Lookup::load(pool).await?;
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await?;
+
+    for city in &export.cities {
+        let city_id = lookup.city_id(&mut tx, &city.name).await?;
+
+        for artist in &city.artists {
+            lookup.artist_id(&mut tx, artist, city_id).await?;
+        }
+
+        for venue in &city.venues {
+            lookup.venue_id(&mut tx, venue, city_id).await?;
+        }
+    }
+
+    for (artist, tags) in &export.tags {
+        // Anyone not in the export's cities has no gigs to restore either
+        let Some(&artist_id) = lookup.artists.get(&artist.to_lowercase()) else {
+            continue;
+        };
+
+        tag::add_artist_tags(&mut tx, artist_id, &tag::parse(tags)).await?;
+    }
+
+    // Names that can't be stored are reported with the gigs they're on
+    for person in &export.people {
+        let _ = lookup.person(&mut tx, person).await?;
+    }
+
+    for user in &export.users {
+        let _ = lookup.user(&mut tx, user).await?;
+    }
+
+    for festival in &export.festivals {
+        // Anything at a festival that can't be restored is reported with its gigs
+        let Some(&venue_id) = lookup.venues.get(&festival.venue.to_lowercase()) else {
+            continue;
+        };
+        let (Ok(start_date), Ok(end_date)) = (
+            festival.start_date.parse::<Date>(),
+            festival.end_date.parse::<Date>(),
+        ) else {
+            continue;
+        };
+
+        let festival = Festival::new(
+            festival.name.clone(),
+            venue_id,
+            start_date,
+            end_date,
+            festival.stages.clone(),
+        );
+        lookup.festival_id(&mut tx, festival).await?;
+    }
+
+    for (idx, exported) in export.gigs.iter().enumerate() {
+        let row = idx as u64 + 1;
+
+        let (date, act) = match parse_date_and_act(&exported.date, &exported.act) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                report.skipped.push(SkippedRow { row, reason });
+                continue;
+            }
+        };
+
+        let Some(&artist_id) = lookup.artists.get(&exported.artist.to_lowercase()) else {
+            report.skipped.push(SkippedRow {
+                row,
+                reason: SkipReason::Unknown("artist", exported.artist.clone()),
+            });
+            continue;
+        };
+
+        let Some(&venue_id) = lookup.venues.get(&exported.venue.to_lowercase()) else {
+            report.skipped.push(SkippedRow {
+                row,
+                reason: SkipReason::Unknown("venue", exported.venue.clone()),
+            });
+            continue;
+        };
+
+        let mut gig = match Gig::new(artist_id, venue_id, date, act)
+            .with_ticket(
+                exported.price.clone(),
+                exported.fees.clone(),
+                exported.currency.clone(),
+                exported.ticket_type.clone(),
+            )
+            .and_then(|gig| gig.with_review(exported.rating, exported.notes.clone()))
+        {
+            Ok(gig) => gig,
+            Err(err) => {
+                report.skipped.push(SkippedRow {
+                    row,
+                    reason: SkipReason::Invalid(Error::from(err).to_string()),
+                });
+                continue;
+            }
+        };
+
+        if let Some(festival) = &exported.festival {
+            gig = match lookup
+                .at_festival(&mut tx, gig, festival, exported.stage.as_deref())
+                .await?
+            {
+                Ok(gig) => gig,
+                Err(reason) => {
+                    report.skipped.push(SkippedRow { row, reason });
+                    continue;
+                }
+            };
+        }
+
+        let mut people = Vec::with_capacity(exported.people.len());
+        let mut users = Vec::with_capacity(exported.users.len());
+        let mut invalid = None;
+        for person in &exported.people {
+            match lookup.person(&mut tx, person).await? {
+                Ok(person) => people.push(person),
+                Err(reason) => invalid = Some(reason),
+            }
+        }
+        for name in &exported.users {
+            match lookup.user(&mut tx, name).await? {
+                Ok(user) => users.push(user),
+                Err(reason) => invalid = Some(reason),
+            }
+        }
+        if let Some(reason) = invalid {
+            report.skipped.push(SkippedRow { row, reason });
+            continue;
+        }
+
+        gig = gig.with_people(people);
+        if users.is_empty() {
+            users.extend(user.cloned());
+        }
+        gig = gig.with_users(users);
+
+        let songs = exported
+            .songs
+            .iter()
+            .map(|song| Song {
+                title: song.title.clone(),
+                encore: song.encore,
+                cover_of: song.cover_of.clone(),
+                notes: song.notes.clone(),
+            })
+            .collect();
+
+        match lookup.insert_gig(&mut tx, gig, songs).await? {
+            Some(reason) => report.skipped.push(SkippedRow { row, reason }),
+            None => report.imported += 1,
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+fn parse_date_and_act(date: &str, act: &str) -> Result<(Date, Act), SkipReason> {
+    let date = date.parse::<Date>().map_err(SkipReason::BadDate)?;
+
+    let Ok(act) = act.parse::<Act>() else {
+        return Err(SkipReason::UnknownAct(act.into()));
+    };
+
+    Ok((date, act))
+}
+
+// `table` is always one of our own table names, never user input
+async fn insert_named(
+    conn: &mut SqliteConnection,
+    table: &str,
+    name: &str,
+    city_id: Option<i64>,
+) -> Result<i64, Error> {
+    let id = match city_id {
+        Some(city_id) => sqlx::query_scalar(&format!(
+            "INSERT INTO \"{table}\" (\"name\", \"city_id\") VALUES ($1, $2) RETURNING \"{table}_id\""
+        ))
+        .bind(name)
+        .bind(city_id)
+        .fetch_one(conn)
+        .await?,
+        None => sqlx::query_scalar(&format!(
+            "INSERT INTO \"{table}\" (\"name\") VALUES ($1) RETURNING \"{table}_id\""
+        ))
+        .bind(name)
+        .fetch_one(conn)
+        .await?,
+    };
+
+    Ok(id)
+}
@@ -1,45 +1,88 @@
 mod act;
 mod artist;
 mod city;
+mod cli;
 mod columns;
+mod database;
 mod dataset;
 mod datatable;
 mod date;
 mod error;
+mod event;
+mod export;
+mod festival;
 mod forms;
+mod fuzzy;
 mod gig;
+mod gigdetail;
+mod graphs;
+mod import;
+mod person;
+mod price;
+mod profile;
+mod query;
+mod rating;
+mod record;
+mod setlist;
+mod tag;
+mod user;
 mod venue;
 
+use clap::Parser;
+use cli::Cli;
 use columns::{
-    data::{DataColumn, TableName},
+    data::{DataColumn, DataColumnEvent, TableName},
     graph::GraphColumn,
     ColumnName,
 };
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use dataset::DataSet;
 use dotenv::dotenv;
 use error::Error;
-use forms::Form;
+use forms::{
+    delete::{DeleteForm, DeleteFormEvent},
+    setlist::{SetlistForm, SetlistFormEvent},
+    user::{UserSelect, UserSelectEvent},
+    Form,
+};
+use profile::{Profile, ProfileEvent};
 use ratatui::{
     layout::{Constraint, Layout},
     prelude::CrosstermBackend,
+    style::Stylize,
+    text::Line,
     Terminal,
 };
-use sqlx::Pool;
-use std::{env, io::Stdout, time::Duration};
+use record::Record;
+use sqlx::{Pool, Sqlite};
+use std::{
+    fs::{self, File},
+    io::Stdout,
+    path::PathBuf,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use user::User;
 
 struct App<'a> {
     terminal: Terminal<CrosstermBackend<Stdout>>,
+    pool: Pool<Sqlite>,
     focused_column: ColumnName,
     data_column: DataColumn<'a>,
     graph_column: GraphColumn,
     render_form: bool,
     form: Form<'a>,
+    delete_form: Option<DeleteForm>,
+    setlist_form: Option<SetlistForm>,
+    user_select: Option<UserSelect>,
+    profile: Option<Profile>,
+    status: Option<String>,
+    // Whose gigs are shown, everyone's when `None`
+    user: Option<User>,
 }
 
 impl<'a> App<'a> {
-    async fn new(db_url: &'a str) -> Result<Self, Error> {
-        let pool = Pool::connect(db_url).await?;
-
+    async fn new(pool: Pool<Sqlite>, user: Option<User>) -> Result<Self, Error> {
         let mut terminal = ratatui::init();
         terminal.clear()?;
 
@@ -48,16 +91,43 @@ impl<'a> App<'a> {
 
         let graph_column = GraphColumn::new(pool.clone()).await?;
 
-        let form = Form::new(pool.clone()).await?;
+        let form = Form::new(pool.clone(), None).await?;
 
-        Ok(Self {
+        let mut app = Self {
             terminal,
+            pool,
             data_column,
             graph_column,
             focused_column: ColumnName::Data,
             render_form: false,
             form,
-        })
+            delete_form: None,
+            setlist_form: None,
+            user_select: None,
+            profile: None,
+            status: None,
+            user: None,
+        };
+
+        if user.is_some() {
+            app.switch_user(user).await?;
+        }
+
+        Ok(app)
+    }
+
+    async fn switch_user(&mut self, user: Option<User>) -> Result<(), Error> {
+        self.status = Some(match &user {
+            Some(user) => format!("Showing {}'s gigs", user.name),
+            None => String::from("Showing everyone's gigs"),
+        });
+
+        self.data_column.set_user(user.as_ref());
+        self.graph_column.set_user(user.clone());
+        self.form.set_user(user.clone());
+        self.user = user;
+
+        self.reload_data().await
     }
 
     async fn reload_data(&mut self) -> Result<(), Error> {
@@ -81,17 +151,32 @@ impl<'a> App<'a> {
     }
 
     async fn handle_event(&mut self) -> Result<bool, Error> {
-        if !event::poll(Duration::from_secs(0))? {
+        if !crossterm::event::poll(Duration::from_secs(0))? {
             return Ok(false);
         }
 
-        let event = event::read()?;
+        let event = crossterm::event::read()?;
 
         if let Event::Key(key) = event {
             match (key.modifiers, key.code) {
-                (KeyModifiers::NONE, KeyCode::Char('+')) => {
+                (KeyModifiers::NONE, KeyCode::Char('+'))
+                    if self.delete_form.is_none()
+                        && self.setlist_form.is_none()
+                        && self.user_select.is_none()
+                        && self.profile.is_none() =>
+                {
                     self.render_form = true;
                 }
+                (KeyModifiers::CONTROL, KeyCode::Char('u'))
+                    if !self.render_form
+                        && self.delete_form.is_none()
+                        && self.setlist_form.is_none()
+                        && self.profile.is_none() =>
+                {
+                    self.user_select =
+                        Some(UserSelect::new(self.pool.clone(), self.user.clone()).await?);
+                    return Ok(false);
+                }
                 (KeyModifiers::NONE, KeyCode::Esc) => {
                     self.render_form = false;
                     self.form.reset_and_reload().await?;
@@ -99,6 +184,12 @@ impl<'a> App<'a> {
                 (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
                     return Ok(true);
                 }
+                (KeyModifiers::CONTROL, KeyCode::Char('e')) => {
+                    self.status = Some(match self.export().await {
+                        Ok(path) => format!("Exported to {}", path.display()),
+                        Err(err) => format!("Export failed: {}", err),
+                    });
+                }
                 (KeyModifiers::CONTROL, KeyCode::Char('h')) => {
                     if matches!(self.focused_column, ColumnName::Graph) {
                         self.focused_column = ColumnName::Data;
@@ -117,22 +208,106 @@ impl<'a> App<'a> {
             }
         }
 
-        if self.render_form {
+        if let Some(profile) = self.profile.as_mut() {
+            if let Some(ProfileEvent::Close) = profile.handle_event(&event) {
+                self.profile = None;
+            }
+        } else if let Some(delete_form) = self.delete_form.as_mut() {
+            if let Some(delete_event) = delete_form.handle_event(&event).await? {
+                if let DeleteFormEvent::Deleted = delete_event {
+                    // Falls back to everyone's gigs when the active user was the one deleted
+                    let users = User::load_all(&self.pool).await?;
+                    if self.user.as_ref().is_some_and(|active| {
+                        !users.iter().any(|user| user.user_id == active.user_id)
+                    }) {
+                        self.switch_user(None).await?;
+                    } else {
+                        self.reload_data().await?;
+                    }
+                }
+
+                self.delete_form = None;
+            }
+        } else if let Some(setlist_form) = self.setlist_form.as_mut() {
+            if let Some(setlist_event) = setlist_form.handle_event(&event).await? {
+                if let SetlistFormEvent::Saved = setlist_event {
+                    self.reload_data().await?;
+                }
+
+                self.setlist_form = None;
+            }
+        } else if let Some(user_select) = self.user_select.as_mut() {
+            if let Some(user_event) = user_select.handle_event(&event).await? {
+                self.user_select = None;
+
+                match user_event {
+                    UserSelectEvent::Select(user) => self.switch_user(user).await?,
+                    UserSelectEvent::Delete(user) => {
+                        self.delete_form =
+                            Some(DeleteForm::new(self.pool.clone(), Record::User(user)).await?);
+                    }
+                    // Picks up anyone added before closing
+                    UserSelectEvent::Close => self.reload_data().await?,
+                }
+            }
+        } else if self.render_form {
             if self.form.handle_event(event).await? {
                 self.reload_data().await?;
 
                 self.render_form = false;
             };
         } else if let ColumnName::Data = self.focused_column {
-            self.data_column.handle_event(event)
+            match self.data_column.handle_event(event) {
+                // Gigs are already shown in full by the detail pane, so opening one edits its setlist
+                Some(DataColumnEvent::Open(Record::Gig(gig))) => {
+                    self.setlist_form = Some(SetlistForm::new(self.pool.clone(), *gig).await?);
+                }
+                Some(DataColumnEvent::Open(record)) => {
+                    self.profile =
+                        Some(Profile::new(self.pool.clone(), record, self.user.as_ref()).await?);
+                }
+                Some(DataColumnEvent::Edit(record)) => {
+                    self.form.edit(record).await?;
+                    self.render_form = true;
+                }
+                Some(DataColumnEvent::Delete(record)) => {
+                    self.delete_form = Some(DeleteForm::new(self.pool.clone(), record).await?);
+                }
+                None => {}
+            }
+        } else {
+            self.graph_column.handle_event(event);
         }
 
         Ok(false)
     }
 
+    async fn export(&self) -> Result<PathBuf, Error> {
+        let exports = database::data_dir()?.join("exports");
+        fs::create_dir_all(&exports)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = exports.join(format!("gig-tracker-{}.json", timestamp));
+
+        export::export_json(File::create(&path)?, &self.pool).await?;
+
+        Ok(path)
+    }
+
     fn render(&mut self) -> Result<(), Error> {
         self.terminal.draw(|frame| {
-            let [left, right] = Layout::horizontal([Constraint::Fill(1); 2]).areas(frame.area());
+            let [columns, status_bar] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(u16::from(self.status.is_some())),
+            ])
+            .areas(frame.area());
+            let [left, right] = Layout::horizontal([Constraint::Fill(1); 2]).areas(columns);
+
+            if let Some(status) = &self.status {
+                frame.render_widget(Line::from(status.as_str()).italic(), status_bar);
+            }
 
             self.data_column.render(frame, left);
             self.graph_column.render(frame, right);
@@ -140,6 +315,22 @@ impl<'a> App<'a> {
             if self.render_form {
                 self.form.render(frame, frame.area());
             }
+
+            if let Some(delete_form) = self.delete_form.as_mut() {
+                delete_form.render(frame, frame.area());
+            }
+
+            if let Some(setlist_form) = self.setlist_form.as_mut() {
+                setlist_form.render(frame, frame.area());
+            }
+
+            if let Some(user_select) = self.user_select.as_mut() {
+                user_select.render(frame, frame.area());
+            }
+
+            if let Some(profile) = self.profile.as_mut() {
+                profile.render(frame, columns);
+            }
         })?;
 
         Ok(())
@@ -150,9 +341,31 @@ impl<'a> App<'a> {
 async fn main() -> Result<(), Error> {
     dotenv().ok();
 
-    let db_url = env::var("DATABASE_URL").expect("env var DATABASE_URL should be set");
+    let cli = Cli::parse();
+
+    let pool = database::connect().await?;
+
+    let user = match &cli.user {
+        Some(name) => match cli::find_by_name::<User>(name, "user", &pool).await {
+            Ok(user) => Some(user),
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(command) = cli.command {
+        if let Err(err) = cli::run(command, user, &pool).await {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+
+        return Ok(());
+    }
 
-    let mut app = App::new(db_url.as_str()).await?;
+    let mut app = App::new(pool, user).await?;
 
     let result = app.run().await;
 
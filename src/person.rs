@@ -0,0 +1,203 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    query::Filter,
+    user::User,
+};
+
+// Someone who comes along to gigs with us
+#[derive(Clone, PartialEq, Eq)]
+pub struct Person {
+    pub person_id: i64,
+    pub name: String,
+    // How many gigs they've been to
+    pub gigs: i64,
+}
+
+impl PartialOrd for Person {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Person {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+impl Person {
+    pub fn new(name: String) -> Self {
+        Self {
+            person_id: 0,
+            name,
+            gigs: 0,
+        }
+    }
+
+    // A gig's attendees are kept as a comma separated list of names
+    pub fn check_name(&self) -> Result<(), Error> {
+        if self.name.contains(',') {
+            return Err(Error::Str(String::from("A name can't contain a comma")));
+        }
+
+        Ok(())
+    }
+}
+
+impl DataSet for Person {
+    async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            Person,
+            r#"
+            SELECT 'p'.'person_id', 'p'.'name',
+                   (SELECT COUNT(*) FROM "gig"
+                    INNER JOIN "attendance" ON "attendance"."event_id" = "gig"."event_id"
+                    WHERE "attendance"."person_id" = "p"."person_id") AS "gigs!: i64"
+            FROM 'person' p"#
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+
+    // With a user in scope, only the gigs they were at are counted
+    async fn load_scoped(pool: &Pool<Sqlite>, scope: &[Filter]) -> Result<Vec<Self>, Error> {
+        let Some(user) = scope.iter().find_map(|filter| match filter {
+            Filter::User(user) => Some(user),
+            _ => None,
+        }) else {
+            return Self::load_all(pool).await;
+        };
+        // Matched here rather than in SQL, where LOWER only folds ASCII
+        let user_id = User::load_all(pool)
+            .await?
+            .into_iter()
+            .find(|own| own.name.to_lowercase() == *user)
+            .map(|own| own.user_id);
+
+        Ok(sqlx::query_as!(
+            Person,
+            r#"
+            SELECT 'p'.'person_id', 'p'.'name',
+                   (SELECT COUNT(*) FROM "gig"
+                    INNER JOIN "attendance" ON "attendance"."event_id" = "gig"."event_id"
+                    INNER JOIN "user_gig" ON "user_gig"."event_id" = "gig"."event_id"
+                                         AND "user_gig"."artist_id" = "gig"."artist_id"
+                    WHERE "attendance"."person_id" = "p"."person_id"
+                      AND "user_gig"."user_id" = $1) AS "gigs!: i64"
+            FROM 'person' p"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+
+    async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        val.check_name()?;
+
+        sqlx::query("INSERT INTO \"person\" (\"name\") VALUES ($1)")
+            .bind(val.name)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        val.check_name()?;
+
+        sqlx::query("UPDATE \"person\" SET \"name\" = $1 WHERE \"person_id\" = $2")
+            .bind(val.name)
+            .bind(original.person_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            DeleteStrategy::Cascade => {}
+            DeleteStrategy::Reassign(replacement) => {
+                // Skips the nights the replacement was already at
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO "attendance" ("event_id", "person_id")
+                    SELECT "event_id", $1 FROM "attendance" WHERE "person_id" = $2"#,
+                )
+                .bind(replacement.person_id)
+                .bind(val.person_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM \"attendance\" WHERE \"person_id\" = $1")
+            .bind(val.person_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM \"person\" WHERE \"person_id\" = $1")
+            .bind(val.person_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let gigs: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM "gig"
+            INNER JOIN "attendance" ON "attendance"."event_id" = "gig"."event_id"
+            WHERE "attendance"."person_id" = $1"#,
+        )
+        .bind(self.person_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(vec![Dependent {
+            table: "gigs",
+            count: gigs,
+        }])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.name.clone(), self.gigs.to_string()]
+    }
+
+    fn key(&self) -> impl Ord + Clone {
+        &self.name
+    }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            1 => self.gigs.cmp(&other.gigs),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
+}
+
+impl Display for Person {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -0,0 +1,32 @@
+// Prices are kept in minor units (pence, cents) so that totals add up exactly
+
+// Parses "12", "12.5" or "12.50" into minor units
+pub fn parse(value: &str) -> Result<i64, String> {
+    let invalid = || format!("\"{}\" isn't a price", value);
+
+    let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+
+    if whole.is_empty() || fraction.len() > 2 {
+        return Err(invalid());
+    }
+
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let whole: i64 = whole.parse().map_err(|_| invalid())?;
+    let fraction: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+
+    whole
+        .checked_mul(100)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+pub fn format(minor: i64) -> String {
+    format!("{}.{:02}", minor / 100, minor % 100)
+}
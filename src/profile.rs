@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, Paragraph, Row, Sparkline, Table, TableState},
+    Frame,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    dataset::DataSet,
+    error::Error,
+    gig::Gig,
+    graphs::act_color,
+    graphs::ACTS,
+    record::Record,
+    setlist::PlayedSong,
+    user::{self, User},
+};
+
+pub enum ProfileEvent {
+    Close,
+}
+
+// A full screen summary of every gig involving an artist, venue, city, festival or person
+pub struct Profile {
+    record: Record,
+    gigs: Vec<Gig>,
+
+    // Every year from the first gig to the last, including the ones without any gigs
+    per_year: BTreeMap<u32, u64>,
+    // Titled lists of names and how many gigs they appear in, most first
+    tallies: Vec<(&'static str, Vec<(String, usize)>)>,
+
+    state: TableState,
+}
+
+impl Profile {
+    // Only counts `user`'s gigs, when there's an active user
+    pub async fn new(
+        pool: Pool<Sqlite>,
+        record: Record,
+        user: Option<&User>,
+    ) -> Result<Self, Error> {
+        let mut gigs: Vec<Gig> = user::gigs_for(user, Gig::load_all(&pool).await?)
+            .into_iter()
+            .filter(|gig| match &record {
+                Record::Artist(artist) => gig.artist_id == artist.artist_id,
+                Record::Venue(venue) => gig.venue_id == venue.venue_id,
+                Record::City(city) => gig.city_id == Some(city.city_id),
+                Record::Gig(other) => gig == other.as_ref(),
+                Record::Festival(festival) => gig.festival_id == Some(festival.festival_id),
+                Record::Person(person) => gig.people.contains(&person.name),
+                Record::User(user) => user.attended(gig),
+            })
+            .collect();
+        gigs.sort_unstable();
+
+        let mut per_year = BTreeMap::new();
+        if let (Some(first), Some(last)) = (gigs.first(), gigs.last()) {
+            for year in first.date.year..=last.date.year {
+                per_year.insert(year, 0);
+            }
+        }
+        for gig in &gigs {
+            *per_year.entry(gig.date.year).or_default() += 1;
+        }
+
+        let artists = tally(&gigs, |gig| gig.artist_name.clone());
+        let venues = tally(&gigs, |gig| gig.venue_name.clone());
+        let years = tally(&gigs, |gig| Some(gig.date.year.to_string()));
+        let stages = tally(&gigs, |gig| gig.stage_name.clone());
+
+        let tallies = match &record {
+            Record::Artist(_) => {
+                let songs: Vec<PlayedSong> = PlayedSong::load_all(&pool)
+                    .await?
+                    .into_iter()
+                    .filter(|song| gigs.iter().any(|gig| song.played_at(gig)))
+                    .collect();
+
+                vec![
+                    ("Venues", venues),
+                    ("Busiest Years", years),
+                    ("Most Heard Songs", tally(&songs, |song| Some(song.name()))),
+                ]
+            }
+            Record::Venue(_) => vec![("Artists", artists), ("Busiest Years", years)],
+            Record::Festival(_) => vec![("Artists", artists), ("Stages", stages)],
+            Record::Person(person) => {
+                // Everyone else who was there on the same nights
+                let others: Vec<&str> = gigs
+                    .iter()
+                    .flat_map(|gig| gig.people.iter().map(String::as_str))
+                    .filter(|name| *name != person.name)
+                    .collect();
+
+                vec![
+                    ("Artists", artists),
+                    ("Venues", venues),
+                    ("Also There", tally(&others, |name| Some(name.to_string()))),
+                ]
+            }
+            Record::City(_) | Record::Gig(_) | Record::User(_) => vec![
+                ("Venues", venues),
+                ("Artists", artists),
+                ("Busiest Years", years),
+            ],
+        };
+
+        let mut state = TableState::default();
+        state.select_first();
+
+        Ok(Self {
+            record,
+            gigs,
+            per_year,
+            tallies,
+            state,
+        })
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<ProfileEvent> {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(ProfileEvent::Close),
+                KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Char('k') => self.state.select_previous(),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let subtitle = match &self.record {
+            Record::Artist(artist) => artist
+                .city_name
+                .as_ref()
+                .map(|city| format!(" from {}", city)),
+            Record::Venue(venue) => venue.city_name.as_ref().map(|city| format!(" in {}", city)),
+            Record::Festival(festival) => Some(format!(
+                " at {}, {}",
+                festival.venue_name.as_deref().unwrap_or_default(),
+                festival.dates()
+            )),
+            Record::City(_) | Record::Gig(_) | Record::Person(_) | Record::User(_) => None,
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .border_style(Style::new().magenta())
+            .title(
+                Line::from(format!(
+                    "{}: {}{}",
+                    self.record.kind(),
+                    self.record,
+                    subtitle.unwrap_or_default()
+                ))
+                .white()
+                .bold()
+                .centered(),
+            )
+            .title_bottom(Line::from("j/k: Scroll  Esc: Close").centered());
+
+        let content_area = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [summary_area, lists_area] =
+            Layout::vertical([Constraint::Length(7), Constraint::Fill(1)]).areas(content_area);
+        let [stats_area, sparkline_area] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Fill(1)]).areas(summary_area);
+        let [gigs_area, tallies_area] =
+            Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(lists_area);
+
+        self.render_stats(frame, stats_area);
+        self.render_sparkline(frame, sparkline_area);
+        self.render_gigs(frame, gigs_area);
+        self.render_tallies(frame, tallies_area);
+    }
+
+    fn render_stats(&self, frame: &mut Frame, area: Rect) {
+        let seen = |gig: Option<&Gig>| gig.map_or("-".to_string(), |gig| gig.date.to_string());
+
+        let mut lines = vec![
+            Line::from(format!("Gigs:         {}", self.gigs.len())),
+            Line::from(format!("First seen:   {}", seen(self.gigs.first()))),
+            Line::from(format!("Last seen:    {}", seen(self.gigs.last()))),
+        ];
+
+        for act in ACTS {
+            lines.push(
+                Line::from(format!(
+                    "{:<14}{}",
+                    format!("{}:", act),
+                    self.gigs.iter().filter(|gig| gig.act == act).count()
+                ))
+                .fg(act_color(&act)),
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Summary")),
+            area,
+        );
+    }
+
+    fn render_sparkline(&self, frame: &mut Frame, area: Rect) {
+        let years = match (
+            self.per_year.keys().next(),
+            self.per_year.keys().next_back(),
+        ) {
+            (Some(first), Some(last)) if first != last => format!(" ({} - {})", first, last),
+            (Some(year), _) => format!(" ({})", year),
+            _ => String::new(),
+        };
+
+        let data: Vec<u64> = self.per_year.values().copied().collect();
+
+        frame.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .style(Style::new().magenta())
+                .block(Block::bordered().title(format!("Gigs per Year{}", years))),
+            area,
+        );
+    }
+
+    fn render_gigs(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<Row> = self
+            .gigs
+            .iter()
+            .map(|gig| {
+                Row::new(vec![
+                    format!("{} {}", gig.date.weekday(), gig.date),
+                    gig.artist_name.clone().unwrap_or_default(),
+                    gig.venue_name.clone().unwrap_or_default(),
+                    gig.act.to_string(),
+                ])
+                .fg(act_color(&gig.act))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(15),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(12),
+            ],
+        )
+        .header(Row::new(vec!["Date", "Artist", "Venue", "Act"]).bold())
+        .row_highlight_style(Style::new().reversed())
+        .block(Block::bordered().title("Gigs"));
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+
+    fn render_tallies(&self, frame: &mut Frame, area: Rect) {
+        let areas = Layout::vertical(vec![Constraint::Fill(1); self.tallies.len()]).split(area);
+
+        for ((title, tally), area) in self.tallies.iter().zip(areas.iter()) {
+            let items: Vec<String> = tally
+                .iter()
+                .map(|(name, count)| format!("{:>3}  {}", count, name))
+                .collect();
+
+            frame.render_widget(
+                List::new(items).block(Block::bordered().title(*title)),
+                *area,
+            );
+        }
+    }
+}
+
+fn tally<T>(values: &[T], name: impl Fn(&T) -> Option<String>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for value in values {
+        if let Some(name) = name(value) {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_unstable_by(|(l_name, l), (r_name, r)| r.cmp(l).then_with(|| l_name.cmp(r_name)));
+
+    counts
+}
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use crate::{act::Act, date::Date};
+
+const OPERATORS: [(&str, Comparison); 6] = [
+    (">=", Comparison::Ge),
+    ("<=", Comparison::Le),
+    (">", Comparison::Gt),
+    ("<", Comparison::Lt),
+    (":", Comparison::Eq),
+    ("=", Comparison::Eq),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    pub fn compare<T: Ord>(&self, left: &T, right: &T) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+// Names are matched case-insensitively, and only need to contain the value
+pub enum Filter {
+    Artist(String),
+    Venue(String),
+    City(String),
+    Festival(String),
+    Act(Act),
+    Year(Comparison, u32),
+    Date(Comparison, Date),
+    Rating(Comparison, i64),
+    // Someone who came along
+    With(String),
+    // One of the artist's genres or other tags
+    Tag(String),
+    // A user who was at the gig, matched in full so one user's name can't pick up another's
+    User(String),
+}
+
+impl Filter {
+    pub fn field(&self) -> &'static str {
+        match self {
+            Filter::Artist(_) => "artist",
+            Filter::Venue(_) => "venue",
+            Filter::City(_) => "city",
+            Filter::Festival(_) => "festival",
+            Filter::Act(_) => "act",
+            Filter::Year(..) => "year",
+            Filter::Date(..) => "date",
+            Filter::Rating(..) => "rating",
+            Filter::With(_) => "with",
+            Filter::Tag(_) => "tag",
+            Filter::User(_) => "user",
+        }
+    }
+
+    fn parse(field: &str, comparison: Comparison, value: &str) -> Result<Self, String> {
+        let field = field.to_lowercase();
+
+        if value.is_empty() {
+            return Err(format!("\"{}\" needs a value", field));
+        }
+
+        let filter = match field.as_str() {
+            "year" => Filter::Year(
+                comparison,
+                value
+                    .parse()
+                    .map_err(|_| format!("\"{}\" isn't a year", value))?,
+            ),
+            "date" => Filter::Date(comparison, value.parse().map_err(|err| format!("{}", err))?),
+            "rating" => Filter::Rating(
+                comparison,
+                value
+                    .parse()
+                    .map_err(|_| format!("\"{}\" isn't a rating", value))?,
+            ),
+            _ if comparison != Comparison::Eq => {
+                return Err(format!("\"{}\" can only be matched with \":\"", field))
+            }
+            "artist" => Filter::Artist(value.to_lowercase()),
+            "venue" => Filter::Venue(value.to_lowercase()),
+            "city" => Filter::City(value.to_lowercase()),
+            "festival" => Filter::Festival(value.to_lowercase()),
+            "with" => Filter::With(value.to_lowercase()),
+            "tag" => Filter::Tag(value.to_lowercase()),
+            "user" => Filter::User(value.to_lowercase()),
+            "act" => Filter::Act(
+                value
+                    .parse()
+                    .map_err(|_| format!("Unknown act \"{}\"", value))?,
+            ),
+            _ => return Err(format!("Unknown field \"{}\"", field)),
+        };
+
+        Ok(filter)
+    }
+}
+
+// A search such as `artist:foals year>=2022 o2`, made of filters on named fields plus any
+// free text, which is fuzzy matched as before
+#[derive(Default)]
+pub struct Query {
+    pub text: String,
+    pub filters: Vec<Filter>,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Query::default();
+        let mut text = Vec::new();
+
+        for token in tokenize(s)? {
+            let operator = OPERATORS
+                .iter()
+                .filter_map(|(operator, comparison)| {
+                    token
+                        .find(operator)
+                        .map(|idx| (idx, *operator, *comparison))
+                })
+                // The first operator in the token, preferring `>=` over `>` at the same place
+                .min_by_key(|(idx, operator, _)| (*idx, usize::MAX - operator.len()));
+
+            match operator {
+                Some((idx, operator, comparison)) if idx > 0 => query.filters.push(Filter::parse(
+                    &token[..idx],
+                    comparison,
+                    &token[idx + operator.len()..],
+                )?),
+                _ => text.push(token),
+            }
+        }
+
+        query.text = text.join(" ");
+
+        Ok(query)
+    }
+}
+
+// Splits on whitespace, apart from inside double quotes so `artist:"arctic monkeys"` is one token
+fn tokenize(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for char in s.chars() {
+        match char {
+            '"' => quoted = !quoted,
+            char if char.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            char => token.push(char),
+        }
+    }
+
+    if quoted {
+        return Err("Missing closing quote".into());
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,21 @@
+// Ratings are whole stars, from 1 up to `MAX`
+pub const MAX: i64 = 5;
+
+pub fn validate(rating: i64) -> Result<i64, String> {
+    if (1..=MAX).contains(&rating) {
+        Ok(rating)
+    } else {
+        Err(format!("A rating is between 1 and {}", MAX))
+    }
+}
+
+// e.g. "★★★☆☆"
+pub fn stars(rating: i64) -> String {
+    let filled = rating.clamp(0, MAX) as usize;
+
+    format!(
+        "{}{}",
+        "★".repeat(filled),
+        "☆".repeat(MAX as usize - filled)
+    )
+}
@@ -0,0 +1,180 @@
+use std::fmt::Display;
+
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    artist::Artist,
+    city::City,
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    festival::Festival,
+    gig::Gig,
+    person::Person,
+    user::User,
+    venue::Venue,
+};
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Record {
+    Artist(Artist),
+    Venue(Venue),
+    // Boxed as a gig carries far more than the other records
+    Gig(Box<Gig>),
+    City(City),
+    Festival(Festival),
+    Person(Person),
+    User(User),
+}
+
+impl Record {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Record::Artist(_) => "Artist",
+            Record::Venue(_) => "Venue",
+            Record::Gig(_) => "Gig",
+            Record::City(_) => "City",
+            Record::Festival(_) => "Festival",
+            Record::Person(_) => "Person",
+            Record::User(_) => "User",
+        }
+    }
+
+    pub async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        match self {
+            Record::Artist(artist) => artist.dependents(pool).await,
+            Record::Venue(venue) => venue.dependents(pool).await,
+            Record::Gig(gig) => gig.dependents(pool).await,
+            Record::City(city) => city.dependents(pool).await,
+            Record::Festival(festival) => festival.dependents(pool).await,
+            Record::Person(person) => person.dependents(pool).await,
+            Record::User(user) => user.dependents(pool).await,
+        }
+    }
+
+    // Every other record of the same type, sorted, for use as a reassignment target
+    pub async fn siblings(&self, pool: &Pool<Sqlite>) -> Result<Vec<Record>, Error> {
+        let mut siblings: Vec<Record> = match self {
+            Record::Artist(_) => sorted(Artist::load_all(pool).await?, Record::Artist),
+            Record::Venue(_) => sorted(Venue::load_all(pool).await?, Record::Venue),
+            Record::Gig(_) => sorted(Gig::load_all(pool).await?, |gig| Record::Gig(Box::new(gig))),
+            Record::City(_) => sorted(City::load_all(pool).await?, Record::City),
+            Record::Festival(_) => sorted(Festival::load_all(pool).await?, Record::Festival),
+            Record::Person(_) => sorted(Person::load_all(pool).await?, Record::Person),
+            Record::User(_) => sorted(User::load_all(pool).await?, Record::User),
+        };
+
+        siblings.retain(|sibling| sibling != self);
+
+        Ok(siblings)
+    }
+
+    pub async fn delete(
+        self,
+        strategy: DeleteStrategy<Record>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        match self {
+            Record::Artist(artist) => {
+                Artist::delete(artist, typed(strategy, Record::into_artist)?, pool).await
+            }
+            Record::Venue(venue) => {
+                Venue::delete(venue, typed(strategy, Record::into_venue)?, pool).await
+            }
+            Record::Gig(gig) => Gig::delete(*gig, typed(strategy, Record::into_gig)?, pool).await,
+            Record::City(city) => {
+                City::delete(city, typed(strategy, Record::into_city)?, pool).await
+            }
+            Record::Festival(festival) => {
+                Festival::delete(festival, typed(strategy, Record::into_festival)?, pool).await
+            }
+            Record::Person(person) => {
+                Person::delete(person, typed(strategy, Record::into_person)?, pool).await
+            }
+            Record::User(user) => {
+                User::delete(user, typed(strategy, Record::into_user)?, pool).await
+            }
+        }
+    }
+
+    fn into_artist(self) -> Option<Artist> {
+        match self {
+            Record::Artist(artist) => Some(artist),
+            _ => None,
+        }
+    }
+
+    fn into_venue(self) -> Option<Venue> {
+        match self {
+            Record::Venue(venue) => Some(venue),
+            _ => None,
+        }
+    }
+
+    fn into_gig(self) -> Option<Gig> {
+        match self {
+            Record::Gig(gig) => Some(*gig),
+            _ => None,
+        }
+    }
+
+    fn into_city(self) -> Option<City> {
+        match self {
+            Record::City(city) => Some(city),
+            _ => None,
+        }
+    }
+
+    fn into_festival(self) -> Option<Festival> {
+        match self {
+            Record::Festival(festival) => Some(festival),
+            _ => None,
+        }
+    }
+
+    fn into_person(self) -> Option<Person> {
+        match self {
+            Record::Person(person) => Some(person),
+            _ => None,
+        }
+    }
+
+    fn into_user(self) -> Option<User> {
+        match self {
+            Record::User(user) => Some(user),
+            _ => None,
+        }
+    }
+}
+
+fn sorted<T: DataSet>(mut values: Vec<T>, into_record: impl Fn(T) -> Record) -> Vec<Record> {
+    values.sort_unstable_by(|l, r| l.key().cmp(&r.key()));
+    values.into_iter().map(into_record).collect()
+}
+
+fn typed<T>(
+    strategy: DeleteStrategy<Record>,
+    from_record: impl Fn(Record) -> Option<T>,
+) -> Result<DeleteStrategy<T>, Error> {
+    Ok(match strategy {
+        DeleteStrategy::Block => DeleteStrategy::Block,
+        DeleteStrategy::Cascade => DeleteStrategy::Cascade,
+        DeleteStrategy::Reassign(record) => DeleteStrategy::Reassign(
+            from_record(record)
+                .ok_or_else(|| Error::Str(String::from("Cannot reassign to a different type")))?,
+        ),
+    })
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Record::Artist(artist) => write!(f, "{}", artist),
+            Record::Venue(venue) => write!(f, "{}", venue),
+            Record::Gig(gig) => write!(f, "{}", gig),
+            Record::City(city) => write!(f, "{}", city),
+            Record::Festival(festival) => write!(f, "{}", festival),
+            Record::Person(person) => write!(f, "{}", person),
+            Record::User(user) => write!(f, "{}", user),
+        }
+    }
+}
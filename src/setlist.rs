@@ -0,0 +1,184 @@
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{error::Error, gig::Gig};
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Song {
+    pub title: String,
+    pub encore: bool,
+    // The original artist, if someone else wrote it
+    pub cover_of: Option<String>,
+    // e.g. "debut" or "acoustic"
+    pub notes: Option<String>,
+}
+
+impl Song {
+    pub fn new(title: String, cover_of: Option<String>, notes: Option<String>) -> Self {
+        Self {
+            title,
+            encore: false,
+            cover_of,
+            notes,
+        }
+    }
+}
+
+// The songs one artist played at a gig, in order
+pub struct Setlist {
+    pub event_id: i64,
+    pub artist_id: i64,
+    pub songs: Vec<Song>,
+}
+
+impl Setlist {
+    pub async fn load(gig: &Gig, pool: &Pool<Sqlite>) -> Result<Self, Error> {
+        let songs = sqlx::query_as!(
+            Song,
+            r#"
+            SELECT 's'.'title', 's'.'encore' AS "encore: bool", 's'.'cover_of', 's'.'notes'
+            FROM 'song' s
+            WHERE 's'.'event_id' = $1 AND 's'.'artist_id' = $2
+            ORDER BY 's'.'position'"#,
+            gig.event_id,
+            gig.artist_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            event_id: gig.event_id,
+            artist_id: gig.artist_id,
+            songs,
+        })
+    }
+
+    // Replaces whatever was saved before
+    pub async fn save(&self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(r#"DELETE FROM "song" WHERE "event_id" = $1 AND "artist_id" = $2"#)
+            .bind(self.event_id)
+            .bind(self.artist_id)
+            .execute(&mut *tx)
+            .await?;
+
+        self.insert(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Adds the songs to a gig that doesn't have any yet
+    pub async fn insert(&self, conn: &mut SqliteConnection) -> Result<(), Error> {
+        for (position, song) in self.songs.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO "song" ("event_id", "artist_id", "position", "title", "encore", "cover_of", "notes")
+                VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            )
+            .bind(self.event_id)
+            .bind(self.artist_id)
+            .bind(position as i64)
+            .bind(&song.title)
+            .bind(song.encore)
+            .bind(&song.cover_of)
+            .bind(&song.notes)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+// A song heard at a gig, used for the stats
+#[derive(Clone)]
+pub struct PlayedSong {
+    pub event_id: i64,
+    pub artist_id: i64,
+    pub artist_name: String,
+    pub title: String,
+    pub cover_of: Option<String>,
+}
+
+impl PlayedSong {
+    pub async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            PlayedSong,
+            r#"
+            SELECT 's'.'event_id', 's'.'artist_id', 'a'.'name' AS "artist_name!",
+                   's'.'title', 's'.'cover_of'
+            FROM 'song' s
+            INNER JOIN 'artist' a ON 'a'.'artist_id' = 's'.'artist_id'"#
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub fn played_at(&self, gig: &Gig) -> bool {
+        self.event_id == gig.event_id && self.artist_id == gig.artist_id
+    }
+
+    // Covers are counted separately from the original artist's own version
+    pub fn name(&self) -> String {
+        match &self.cover_of {
+            Some(original) => format!("{} ({} cover)", self.title, original),
+            None => self.title.clone(),
+        }
+    }
+}
+
+// Keeps the setlist with a gig that's moved to another event, before the old gig is removed
+pub async fn move_songs(
+    conn: &mut SqliteConnection,
+    original: &Gig,
+    event_id: i64,
+    artist_id: i64,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE "song" SET "event_id" = $1, "artist_id" = $2
+        WHERE "event_id" = $3 AND "artist_id" = $4"#,
+    )
+    .bind(event_id)
+    .bind(artist_id)
+    .bind(original.event_id)
+    .bind(original.artist_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// Puts `from`'s setlist after whatever `to` already has, both given as (event_id, artist_id)
+pub async fn append_songs(
+    conn: &mut SqliteConnection,
+    (from_event, from_artist): (i64, i64),
+    (to_event, to_artist): (i64, i64),
+) -> Result<(), Error> {
+    let offset: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(MAX("position") + 1, 0) FROM "song"
+        WHERE "event_id" = $1 AND "artist_id" = $2"#,
+    )
+    .bind(to_event)
+    .bind(to_artist)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE "song" SET "event_id" = $1, "artist_id" = $2, "position" = "position" + $3
+        WHERE "event_id" = $4 AND "artist_id" = $5"#,
+    )
+    .bind(to_event)
+    .bind(to_artist)
+    .bind(offset)
+    .bind(from_event)
+    .bind(from_artist)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
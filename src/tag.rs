@@ -0,0 +1,76 @@
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::error::Error;
+
+// Every tag given to an artist, for suggesting while typing
+pub async fn load_names(pool: &Pool<Sqlite>) -> Result<Vec<String>, Error> {
+    Ok(
+        sqlx::query_scalar(r#"SELECT "name" FROM "tag" ORDER BY "name" COLLATE NOCASE"#)
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+// Tags are entered one at a time or as a comma separated list, blanks and repeats are dropped
+pub fn parse(tags: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for name in tags.iter().flat_map(|tags| tags.split(',')).map(str::trim) {
+        if !name.is_empty() && !names.iter().any(|added| added.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+
+    names
+}
+
+// Replaces the artist's tags with `names`, creating any tags that don't exist yet
+pub async fn set_artist_tags(
+    conn: &mut SqliteConnection,
+    artist_id: i64,
+    names: &[String],
+) -> Result<(), Error> {
+    sqlx::query(r#"DELETE FROM "artist_tag" WHERE "artist_id" = $1"#)
+        .bind(artist_id)
+        .execute(&mut *conn)
+        .await?;
+
+    add_artist_tags(conn, artist_id, names).await?;
+
+    remove_unused(conn).await
+}
+
+// Gives the artist any of `names` they don't have yet, creating any tags that don't exist yet
+pub async fn add_artist_tags(
+    conn: &mut SqliteConnection,
+    artist_id: i64,
+    names: &[String],
+) -> Result<(), Error> {
+    for name in names {
+        sqlx::query(r#"INSERT OR IGNORE INTO "tag" ("name") VALUES ($1)"#)
+            .bind(name)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO "artist_tag" ("artist_id", "tag_id")
+            SELECT $1, "tag_id" FROM "tag" WHERE "name" = $2"#,
+        )
+        .bind(artist_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// A tag nobody has isn't worth suggesting
+pub async fn remove_unused(conn: &mut SqliteConnection) -> Result<(), Error> {
+    sqlx::query(r#"DELETE FROM "tag" WHERE "tag_id" NOT IN (SELECT "tag_id" FROM "artist_tag")"#)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
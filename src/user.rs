@@ -0,0 +1,247 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    event,
+    gig::Gig,
+    query::Filter,
+};
+
+// One of the people sharing the database. Artists, venues and cities are shared by everyone,
+// but each user has their own gigs
+#[derive(Clone, PartialEq, Eq)]
+pub struct User {
+    pub user_id: i64,
+    pub name: String,
+    // How many gigs they've been to
+    pub gigs: i64,
+}
+
+impl PartialOrd for User {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for User {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+impl User {
+    pub fn new(name: String) -> Self {
+        Self {
+            user_id: 0,
+            name,
+            gigs: 0,
+        }
+    }
+
+    // A gig's users are kept as a comma separated list of names
+    pub fn check_name(&self) -> Result<(), Error> {
+        if self.name.contains(',') {
+            return Err(Error::Str(String::from("A name can't contain a comma")));
+        }
+
+        Ok(())
+    }
+
+    // Whether the user was at `gig`
+    pub fn attended(&self, gig: &Gig) -> bool {
+        gig.users.contains(&self.name)
+    }
+}
+
+impl DataSet for User {
+    async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
+        Ok(sqlx::query_as!(
+            User,
+            r#"
+            SELECT 'u'.'user_id', 'u'.'name',
+                   (SELECT COUNT(*) FROM "user_gig"
+                    WHERE "user_gig"."user_id" = "u"."user_id") AS "gigs!: i64"
+            FROM 'user' u"#
+        )
+        .fetch_all(pool)
+        .await?)
+    }
+
+    async fn save(val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        val.check_name()?;
+
+        sqlx::query("INSERT INTO \"user\" (\"name\") VALUES ($1)")
+            .bind(val.name)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        val.check_name()?;
+
+        sqlx::query("UPDATE \"user\" SET \"name\" = $1 WHERE \"user_id\" = $2")
+            .bind(val.name)
+            .bind(original.user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            // Gigs nobody else was at are removed along with them, shared ones are kept for the
+            // others
+            DeleteStrategy::Cascade => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM "gig" WHERE ("event_id", "artist_id") IN (
+                        SELECT "event_id", "artist_id" FROM "user_gig" WHERE "user_id" = $1
+                        EXCEPT
+                        SELECT "event_id", "artist_id" FROM "user_gig" WHERE "user_id" != $1
+                    )"#,
+                )
+                .bind(val.user_id)
+                .execute(&mut *tx)
+                .await?;
+
+                event::remove_empty(&mut tx).await?;
+            }
+            DeleteStrategy::Reassign(replacement) => {
+                // Skips the gigs the replacement was already at
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO "user_gig" ("user_id", "event_id", "artist_id")
+                    SELECT $1, "event_id", "artist_id" FROM "user_gig" WHERE "user_id" = $2"#,
+                )
+                .bind(replacement.user_id)
+                .bind(val.user_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM \"user\" WHERE \"user_id\" = $1")
+            .bind(val.user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let gigs: i64 =
+            sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user_gig" WHERE "user_id" = $1"#)
+                .bind(self.user_id)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(vec![Dependent {
+            table: "gigs",
+            count: gigs,
+        }])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.name.clone(), self.gigs.to_string()]
+    }
+
+    fn key(&self) -> impl Ord + Clone {
+        &self.name
+    }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            1 => self.gigs.cmp(&other.gigs),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
+}
+
+impl Display for User {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+// Scopes a table to `user`'s gigs, or leaves it showing everyone's when there's no user
+pub fn scope(user: Option<&User>) -> Vec<Filter> {
+    user.map(|user| vec![Filter::User(user.name.to_lowercase())])
+        .unwrap_or_default()
+}
+
+// Only the gigs `user` was at, or everyone's when there's no user
+pub fn gigs_for(user: Option<&User>, gigs: Vec<Gig>) -> Vec<Gig> {
+    gigs.into_iter()
+        .filter(|gig| user.is_none_or(|user| user.attended(gig)))
+        .collect()
+}
+
+// Replaces the users who were at the gig for `gig`'s artist in the event with `gig`'s users
+pub async fn set_attended(
+    conn: &mut SqliteConnection,
+    event_id: i64,
+    gig: &Gig,
+) -> Result<(), Error> {
+    sqlx::query(r#"DELETE FROM "user_gig" WHERE "event_id" = $1 AND "artist_id" = $2"#)
+        .bind(event_id)
+        .bind(gig.artist_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for name in &gig.users {
+        sqlx::query(
+            r#"
+            INSERT INTO "user_gig" ("user_id", "event_id", "artist_id")
+            SELECT "user_id", $1, $2 FROM "user" WHERE "name" = $3"#,
+        )
+        .bind(event_id)
+        .bind(gig.artist_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Puts everyone who was at `from` down as being at `to` instead, skipping anyone already there.
+// Both are given as (event_id, artist_id)
+pub async fn move_attended(
+    conn: &mut SqliteConnection,
+    (from_event, from_artist): (i64, i64),
+    (to_event, to_artist): (i64, i64),
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO "user_gig" ("user_id", "event_id", "artist_id")
+        SELECT "user_id", $1, $2 FROM "user_gig" WHERE "event_id" = $3 AND "artist_id" = $4"#,
+    )
+    .bind(to_event)
+    .bind(to_artist)
+    .bind(from_event)
+    .bind(from_artist)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
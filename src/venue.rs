@@ -1,16 +1,26 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
-use ratatui::widgets::{ListItem, Row};
 use sqlx::{Pool, Sqlite};
 
-use crate::{dataset::DataSet, error::Error};
+use crate::{
+    dataset::{DataSet, DeleteStrategy, Dependent},
+    error::Error,
+    event, festival,
+    query::Filter,
+};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Venue {
     pub venue_id: i64,
-    name: String,
-    city_id: i64,
-    city_name: Option<String>,
+    pub name: String,
+    pub city_id: i64,
+    pub city_name: Option<String>,
+}
+
+impl PartialOrd for Venue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for Venue {
@@ -31,6 +41,8 @@ impl Venue {
 }
 
 impl DataSet for Venue {
+    const FILTERS: &'static [&'static str] = &["city"];
+
     async fn load_all(pool: &Pool<Sqlite>) -> Result<Vec<Self>, Error> {
         Ok(sqlx::query_as!(Venue, "SELECT 'v'.'venue_id', 'v'.'name', 'v'.'city_id', 'c'.'name' AS 'city_name' FROM 'venue' v INNER JOIN 'city' c ON 'c'.'city_id' = 'v'.'city_id'")
             .fetch_all(pool)
@@ -47,19 +59,147 @@ impl DataSet for Venue {
         Ok(())
     }
 
-    fn contains(&self, val: String) -> bool {
-        let search = val.as_str().to_lowercase();
+    async fn update(original: &Self, val: Self, pool: &Pool<Sqlite>) -> Result<(), Error> {
+        sqlx::query("UPDATE \"venue\" SET \"name\" = $1, \"city_id\" = $2 WHERE \"venue_id\" = $3")
+            .bind(val.name)
+            .bind(val.city_id)
+            .bind(original.venue_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        val: Self,
+        strategy: DeleteStrategy<Self>,
+        pool: &Pool<Sqlite>,
+    ) -> Result<(), Error> {
+        if let DeleteStrategy::Block = strategy {
+            val.ensure_unreferenced(pool).await?;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        match strategy {
+            DeleteStrategy::Block => {}
+            DeleteStrategy::Cascade => {
+                sqlx::query(
+                    r#"
+                    DELETE FROM "gig"
+                    WHERE "event_id" IN (SELECT "event_id" FROM "event" WHERE "venue_id" = $1)"#,
+                )
+                .bind(val.venue_id)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query("DELETE FROM \"event\" WHERE \"venue_id\" = $1")
+                    .bind(val.venue_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                festival::remove_at(&mut tx, val.venue_id).await?;
+            }
+            DeleteStrategy::Reassign(replacement) => {
+                // Nights the replacement already has an event for are merged into that event
+                let merged: Vec<(i64, i64)> = sqlx::query_as(
+                    r#"
+                    SELECT "old"."event_id", "kept"."event_id" FROM "event" "old"
+                    INNER JOIN "event" "kept" ON "kept"."date" = "old"."date"
+                    WHERE "old"."venue_id" = $2 AND "kept"."venue_id" = $1"#,
+                )
+                .bind(replacement.venue_id)
+                .bind(val.venue_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+                for (old, kept) in merged {
+                    event::merge(&mut tx, old, kept).await?;
+                }
+
+                sqlx::query("UPDATE \"event\" SET \"venue_id\" = $1 WHERE \"venue_id\" = $2")
+                    .bind(replacement.venue_id)
+                    .bind(val.venue_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("UPDATE \"festival\" SET \"venue_id\" = $1 WHERE \"venue_id\" = $2")
+                    .bind(replacement.venue_id)
+                    .bind(val.venue_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM \"venue\" WHERE \"venue_id\" = $1")
+            .bind(val.venue_id)
+            .execute(&mut *tx)
+            .await?;
 
-        self.name.to_lowercase().contains(&search)
-            || self
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn dependents(&self, pool: &Pool<Sqlite>) -> Result<Vec<Dependent>, Error> {
+        let gigs: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM "gig"
+            INNER JOIN "event" ON "event"."event_id" = "gig"."event_id"
+            WHERE "event"."venue_id" = $1"#,
+        )
+        .bind(self.venue_id)
+        .fetch_one(pool)
+        .await?;
+
+        let festivals: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM \"festival\" WHERE \"venue_id\" = $1")
+                .bind(self.venue_id)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(vec![
+            Dependent {
+                table: "gigs",
+                count: gigs,
+            },
+            Dependent {
+                table: "festivals",
+                count: festivals,
+            },
+        ])
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.venue_id.to_string(),
+            self.name.clone(),
+            self.city_name.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn matches(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::City(city) => self
                 .city_name
                 .as_ref()
-                .is_some_and(|name| name.to_lowercase().contains(&search))
+                .is_some_and(|name| name.to_lowercase().contains(city)),
+            _ => true,
+        }
     }
 
     fn key(&self) -> impl Ord + Clone {
         &self.name
     }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            0 => self.venue_id.cmp(&other.venue_id),
+            2 => self.city_name.cmp(&other.city_name),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| self.key().cmp(&other.key()))
+    }
 }
 
 impl Display for Venue {
@@ -67,19 +207,3 @@ impl Display for Venue {
         write!(f, "{}", self.name)
     }
 }
-
-impl From<Venue> for Row<'_> {
-    fn from(value: Venue) -> Self {
-        Row::new(vec![
-            value.venue_id.to_string(),
-            value.name,
-            value.city_name.unwrap(),
-        ])
-    }
-}
-
-impl From<Venue> for ListItem<'_> {
-    fn from(value: Venue) -> Self {
-        ListItem::new(value.name.clone())
-    }
-}